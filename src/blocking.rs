@@ -0,0 +1,283 @@
+//! Synchronous wrapper around [`crate::TwitterScraper`].
+//!
+//! Like `reqwest::blocking`, every call is driven to completion on a private
+//! current-thread runtime, so these methods must not be called from within an
+//! async context.
+
+use std::{collections::HashMap, pin::Pin};
+
+use futures::{Stream, StreamExt};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    search::SearchMode,
+    login::LoginChallenge,
+    rate_limit::RateLimit,
+    session::Session,
+    types::{conversation::Conversation, profile::Profile, trend::Trend, tweet::Tweet},
+    Result,
+};
+
+pub struct TwitterScraper {
+    inner: crate::TwitterScraper,
+    runtime: Runtime,
+}
+
+pub struct Iter<'a, T> {
+    runtime: &'a Runtime,
+    stream: Pin<Box<dyn Stream<Item = Result<T>> + 'a>>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl TwitterScraper {
+    pub fn new() -> Self {
+        Self::from_async(crate::TwitterScraper::new())
+    }
+
+    pub fn from_async(inner: crate::TwitterScraper) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start blocking runtime");
+
+        Self { inner, runtime }
+    }
+
+    pub fn add_csrf_auth<T: Into<String>>(mut self, auth_token: T, csrf_token: T) -> Self {
+        self.inner = self.inner.add_csrf_auth(auth_token, csrf_token);
+        self
+    }
+
+    pub fn with_delay(mut self, delay: std::time::Duration) -> Self {
+        self.inner = self.inner.with_delay(delay);
+        self
+    }
+
+    pub fn rate_limit(&self, endpoint: &str) -> Option<RateLimit> {
+        self.inner.rate_limit(endpoint)
+    }
+
+    pub fn rate_limits(&self) -> HashMap<String, RateLimit> {
+        self.inner.rate_limits()
+    }
+
+    pub fn proxies(&self) -> Vec<Option<String>> {
+        self.inner.proxies()
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.inner = self.inner.with_session(session);
+        self
+    }
+
+    pub fn set_session(&self, session: Option<Session>) {
+        self.inner.set_session(session)
+    }
+
+    pub fn save_session<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        self.inner.save_session(path)
+    }
+
+    pub fn load_session<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        self.inner.load_session(path)
+    }
+
+    pub fn login<F>(&self, username: &str, password: &str, challenge: F) -> Result<Session>
+    where
+        F: FnMut(LoginChallenge) -> Option<String> + Send,
+    {
+        self.runtime
+            .block_on(self.inner.login(username, password, challenge))
+    }
+
+    pub fn logout(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.logout())
+    }
+
+    pub fn get_guest_token(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.get_guest_token())
+    }
+
+    pub fn get_users_tweets(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        self.runtime
+            .block_on(self.inner.get_users_tweets(username, cursor))
+    }
+
+    pub fn search(
+        &self,
+        query: &str,
+        mode: SearchMode,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        self.runtime.block_on(self.inner.search(query, mode, cursor))
+    }
+
+    pub fn search_profiles(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.runtime
+            .block_on(self.inner.search_profiles(query, cursor))
+    }
+
+    pub fn get_trends(&self) -> Result<Vec<Trend>> {
+        self.runtime.block_on(self.inner.get_trends())
+    }
+
+    pub fn get_trends_for_location(&self, woeid: i64) -> Result<Vec<Trend>> {
+        self.runtime
+            .block_on(self.inner.get_trends_for_location(woeid))
+    }
+
+    pub fn get_profile(&self, username: &str) -> Result<Profile> {
+        self.runtime.block_on(self.inner.get_profile(username))
+    }
+
+    pub fn get_tweet(&self, id: &str) -> Result<Tweet> {
+        self.runtime.block_on(self.inner.get_tweet(id))
+    }
+
+    pub fn get_conversation(&self, id: &str) -> Result<(Conversation, Option<String>)> {
+        self.runtime.block_on(self.inner.get_conversation(id))
+    }
+
+    pub fn get_more_replies(
+        &self,
+        conversation: &mut Conversation,
+        cursor: String,
+    ) -> Result<Option<String>> {
+        self.runtime
+            .block_on(self.inner.get_more_replies(conversation, cursor))
+    }
+
+    pub fn get_followers(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.runtime
+            .block_on(self.inner.get_followers(username, cursor))
+    }
+
+    pub fn get_following(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.runtime
+            .block_on(self.inner.get_following(username, cursor))
+    }
+
+    pub fn tweets_iter<'a>(&'a self, username: &'a str, limit: Option<usize>) -> Iter<'a, Tweet> {
+        self.iter(self.inner.tweets_stream(username, limit))
+    }
+
+    pub fn search_iter<'a>(
+        &'a self,
+        query: &'a str,
+        mode: SearchMode,
+        limit: Option<usize>,
+    ) -> Iter<'a, Tweet> {
+        self.iter(self.inner.search_stream(query, mode, limit))
+    }
+
+    pub fn search_profiles_iter<'a>(
+        &'a self,
+        query: &'a str,
+        limit: Option<usize>,
+    ) -> Iter<'a, Profile> {
+        self.iter(self.inner.search_profiles_stream(query, limit))
+    }
+
+    pub fn followers_iter<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> Iter<'a, Profile> {
+        self.iter(self.inner.followers_stream(username, limit))
+    }
+
+    pub fn following_iter<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> Iter<'a, Profile> {
+        self.iter(self.inner.following_stream(username, limit))
+    }
+
+    fn iter<'a, T>(&'a self, stream: impl Stream<Item = Result<T>> + 'a) -> Iter<'a, T> {
+        Iter {
+            runtime: &self.runtime,
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl Default for TwitterScraper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_blocking_scraper() {
+    use crate::mock::{self, MockTwitter};
+
+    // The mock server is started from its own runtime; the scraper runs without one.
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let mock = runtime.block_on(MockTwitter::start());
+    let scraper = TwitterScraper::from_async(mock.scraper());
+
+    scraper.get_guest_token().unwrap();
+    assert_eq!(scraper.get_profile("Twitter").unwrap().username, "Twitter");
+    assert!(scraper.rate_limits().is_empty());
+
+    let (tweets, cursor) = scraper.search("bitcoin", SearchMode::Top, None).unwrap();
+    assert!(!tweets.is_empty());
+    assert!(cursor.is_some());
+
+    let (followers, _) = scraper.get_followers("Twitter", None).unwrap();
+    assert_eq!(followers[0].username, "jack");
+
+    let session = scraper
+        .login("jack", mock::LOGIN_PASSWORD, |_| Some(mock::LOGIN_EMAIL.to_owned()))
+        .unwrap();
+    assert!(session.is_authenticated());
+    scraper.logout().unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn test_blocking_iter() {
+    use crate::mock::MockTwitter;
+
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let mock = runtime.block_on(MockTwitter::start());
+    let scraper = TwitterScraper::from_async(mock.scraper());
+
+    let followers = scraper
+        .followers_iter("Twitter", Some(3))
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(followers.len(), 3);
+
+    let mut tweets = scraper.search_iter("bitcoin", SearchMode::Latest, Some(1));
+    assert!(tweets.next().unwrap().is_ok());
+    assert!(tweets.next().is_none());
+
+    let error = scraper.get_profile("not_found").unwrap_err();
+    assert!(matches!(error, crate::Error::UserNotFound));
+}
@@ -0,0 +1,307 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE, AUTHORIZATION, USER_AGENT},
+    Client, ClientBuilder, Proxy,
+};
+
+use crate::{
+    cassette::{Cassette, CassetteMode},
+    egress::{Egress, EgressPool},
+    endpoints::Endpoints,
+    pacing::{Pacer, Pacing},
+    rate_limit::{RateLimitPolicy, RateLimits},
+    retry::RetryPolicy,
+    scraper::BEARER_TOKEN,
+    token::{GuestTokenCache, DEFAULT_GUEST_TOKEN_LIFETIME},
+    session::{Cookie, Session},
+    Error, Result, TwitterScraper,
+};
+
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+pub struct TwitterScraperBuilder {
+    client: Option<Client>,
+    proxy: Option<String>,
+    proxies: Vec<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+    headers: Vec<(String, String)>,
+    guest_token_lifetime: Duration,
+    session: Option<Session>,
+    endpoints: Endpoints,
+    cassette: Option<CassetteMode>,
+    rate_limit_policy: RateLimitPolicy,
+    retry_policy: RetryPolicy,
+    pacing: Pacing,
+}
+
+impl TwitterScraperBuilder {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            proxy: None,
+            proxies: vec![],
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            accept_language: None,
+            headers: vec![],
+            guest_token_lifetime: DEFAULT_GUEST_TOKEN_LIFETIME,
+            session: None,
+            endpoints: Endpoints::default(),
+            cassette: None,
+            rate_limit_policy: RateLimitPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            pacing: Pacing::default(),
+        }
+    }
+
+    /// HTTP(S) or SOCKS5 proxy, e.g. `socks5://localhost:1080`.
+    pub fn proxy<T: Into<String>>(mut self, proxy: T) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Spreads requests over several proxies, each with its own client and guest
+    /// token since guest tokens are tied to the IP that activated them.
+    pub fn proxies<I, T>(mut self, proxies: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.proxies = proxies.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn accept_language<T: Into<String>>(mut self, accept_language: T) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    pub fn header<T: Into<String>>(mut self, name: T, value: T) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends requests through `client`. Proxy and timeout options cannot be
+    /// combined with a caller-supplied client; headers are still applied.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn guest_token_lifetime(mut self, lifetime: Duration) -> Self {
+        self.guest_token_lifetime = lifetime;
+        self
+    }
+
+    pub fn csrf_auth<T: Into<String>>(self, auth_token: T, csrf_token: T) -> Self {
+        let mut session = self.session.clone().unwrap_or_default();
+        session.insert(Cookie::new(String::from("auth_token"), auth_token.into()));
+        session.insert(Cookie::new(String::from("ct0"), csrf_token.into()));
+        self.session(session)
+    }
+
+    /// Cookies sent with every request, e.g. from a logged-in browser.
+    pub fn session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Records traffic to, or replays it from, a cassette file.
+    pub fn cassette(mut self, mode: CassetteMode) -> Self {
+        self.cassette = Some(mode);
+        self
+    }
+
+    pub fn rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limit_policy = policy;
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    pub fn build(self) -> Result<TwitterScraper> {
+        let headers = self.build_headers()?;
+        if self.proxy.is_some() && !self.proxies.is_empty() {
+            return Err(Error::InvalidConfig(
+                "proxy and proxies cannot be combined".to_owned(),
+            ));
+        }
+
+        let egress = |proxy: Option<String>, client| Egress {
+            proxy,
+            client,
+            guest_token: GuestTokenCache::new(self.guest_token_lifetime),
+            rate_limits: RateLimits::new(self.rate_limit_policy),
+        };
+        let egresses = match self.client.clone() {
+            Some(client) => {
+                if self.proxy.is_some()
+                    || !self.proxies.is_empty()
+                    || self.timeout.is_some()
+                    || self.connect_timeout.is_some()
+                {
+                    return Err(Error::InvalidConfig(
+                        "proxy and timeouts cannot be applied to a caller-supplied client"
+                            .to_owned(),
+                    ));
+                }
+                vec![egress(None, client)]
+            }
+            None if !self.proxies.is_empty() => self
+                .proxies
+                .iter()
+                .map(|proxy| Ok(egress(Some(proxy.to_owned()), self.build_client(Some(proxy))?)))
+                .collect::<Result<_>>()?,
+            None => vec![egress(
+                self.proxy.clone(),
+                self.build_client(self.proxy.as_deref())?,
+            )],
+        };
+
+        let cassette = match self.cassette {
+            Some(mode) => Some(Arc::new(Cassette::open(mode)?)),
+            None => None,
+        };
+
+        Ok(TwitterScraper {
+            egress: Arc::new(EgressPool::new(egresses)),
+            headers,
+            endpoints: self.endpoints,
+            session: Arc::new(RwLock::new(self.session)),
+            cassette,
+            retry_policy: self.retry_policy,
+            pacer: Arc::new(Pacer::new(self.pacing)),
+        })
+    }
+
+    pub(crate) fn build_client(&self, proxy: Option<&str>) -> Result<Client> {
+        let mut builder = ClientBuilder::new();
+
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(parse_proxy(proxy)?);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        builder.build().map_err(Error::from)
+    }
+
+    fn build_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static(BEARER_TOKEN));
+
+        if let Some(user_agent) = &self.user_agent {
+            headers.insert(USER_AGENT, header_value(user_agent)?);
+        }
+        if let Some(accept_language) = &self.accept_language {
+            headers.insert(ACCEPT_LANGUAGE, header_value(accept_language)?);
+        }
+        for (name, value) in self.headers.iter() {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidHeader(name.to_owned()))?;
+            headers.append(name, header_value(value)?);
+        }
+
+        Ok(headers)
+    }
+}
+
+impl Default for TwitterScraperBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(value.to_owned()))
+}
+
+fn parse_proxy(proxy: &str) -> Result<Proxy> {
+    let url = reqwest::Url::parse(proxy).map_err(|_| Error::InvalidProxy(proxy.to_owned()))?;
+    if !PROXY_SCHEMES.contains(&url.scheme()) || url.host_str().is_none() {
+        return Err(Error::InvalidProxy(proxy.to_owned()));
+    }
+
+    Proxy::all(url).map_err(|_| Error::InvalidProxy(proxy.to_owned()))
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_builder_validation() {
+    let scraper = TwitterScraper::builder()
+        .proxy("socks5://localhost:1080")
+        .timeout(Duration::from_secs(10))
+        .user_agent("twitter-scraper")
+        .header("x-twitter-client-language", "en")
+        .build();
+    assert!(scraper.is_ok());
+
+    let proxy = TwitterScraper::builder().proxy("ftp://localhost").build();
+    assert!(matches!(proxy, Err(Error::InvalidProxy(_))));
+
+    let header = TwitterScraper::builder().header("bad header", "x").build();
+    assert!(matches!(header, Err(Error::InvalidHeader(_))));
+
+    let client = TwitterScraper::builder()
+        .client(Client::new())
+        .timeout(Duration::from_secs(1))
+        .build();
+    assert!(matches!(client, Err(Error::InvalidConfig(_))));
+
+    let proxies = TwitterScraper::builder()
+        .proxies(["http://localhost:8080", "socks5://localhost:1080"])
+        .build()
+        .unwrap();
+    assert_eq!(proxies.proxies().len(), 2);
+
+    let both = TwitterScraper::builder()
+        .proxy("http://localhost:8080")
+        .proxies(["http://localhost:8081"])
+        .build();
+    assert!(matches!(both, Err(Error::InvalidConfig(_))));
+
+    let invalid = TwitterScraper::builder()
+        .proxies(["http://localhost:8080", "ftp://localhost"])
+        .build();
+    assert!(matches!(invalid, Err(Error::InvalidProxy(_))));
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{session::create_private, Error, Result};
+
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    /// Sends requests normally and appends every request/response pair to the
+    /// file, one JSON object per line. The file is replaced and readable by the
+    /// owner only, as it holds guest tokens and session cookies.
+    Record(PathBuf),
+    /// Serves responses from the file without touching the network.
+    Replay(PathBuf),
+}
+
+pub(crate) struct RawResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+pub(crate) struct Cassette {
+    mode: CassetteMode,
+    interactions: Mutex<Vec<Interaction>>,
+    recording: Option<Mutex<File>>,
+    /// Replay position per request, so repeated requests get their responses in order.
+    positions: Mutex<HashMap<RecordedRequest, usize>>,
+}
+
+impl Cassette {
+    pub fn open(mode: CassetteMode) -> Result<Self> {
+        let (interactions, recording) = match &mode {
+            CassetteMode::Record(path) => {
+                match fs::remove_file(path) {
+                    Err(error) if error.kind() != ErrorKind::NotFound => return Err(error.into()),
+                    _ => {}
+                }
+                (vec![], Some(Mutex::new(create_private(path)?)))
+            }
+            CassetteMode::Replay(path) => {
+                let interactions = fs::read_to_string(path)?
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(serde_json::from_str)
+                    .collect::<serde_json::Result<_>>()?;
+                (interactions, None)
+            }
+        };
+
+        Ok(Self {
+            mode,
+            interactions: Mutex::new(interactions),
+            recording,
+            positions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self.mode, CassetteMode::Replay(_))
+    }
+
+    pub fn replay(&self, method: &Method, url: &Url) -> Result<RawResponse> {
+        let request = RecordedRequest::new(method, url);
+        let interactions = self.interactions.lock().unwrap();
+        let matching: Vec<&Interaction> = interactions
+            .iter()
+            .filter(|i| i.request == request)
+            .collect();
+
+        let mut positions = self.positions.lock().unwrap();
+        let position = positions.entry(request).or_insert(0);
+        let interaction = matching
+            .get(*position)
+            .or_else(|| matching.last())
+            .ok_or_else(|| Error::CassetteMiss(format!("{} {}", method, url)))?;
+        *position += 1;
+
+        interaction.response.to_raw()
+    }
+
+    pub fn record(&self, method: &Method, url: &Url, response: &RawResponse) -> Result<()> {
+        let recording = match &self.recording {
+            Some(recording) => recording,
+            None => return Ok(()),
+        };
+
+        let interaction = Interaction {
+            request: RecordedRequest::new(method, url),
+            response: RecordedResponse::from_raw(response),
+        };
+        let mut line = serde_json::to_vec(&interaction)?;
+        line.push(b'\n');
+        recording.lock().unwrap().write_all(&line)?;
+        Ok(())
+    }
+}
+
+impl RecordedRequest {
+    fn new(method: &Method, url: &Url) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn from_raw(response: &RawResponse) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_owned()))
+            })
+            .collect();
+
+        Self {
+            status: response.status.as_u16(),
+            headers,
+            body: response.body.to_owned(),
+        }
+    }
+
+    fn to_raw(&self) -> Result<RawResponse> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers.iter() {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidHeader(name.to_owned()))?;
+            let value =
+                HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(value.to_owned()))?;
+            headers.append(name, value);
+        }
+
+        Ok(RawResponse {
+            status: StatusCode::from_u16(self.status)
+                .map_err(|_| Error::InvalidConfig(format!("invalid status {}", self.status)))?,
+            headers,
+            body: self.body.to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_record_and_replay() {
+    use crate::{mock::MockTwitter, TwitterScraper};
+
+    let path = std::env::temp_dir().join(format!("twitter-scraper-{}.json", std::process::id()));
+
+    let mock = MockTwitter::start().await;
+    let endpoints = mock.endpoints();
+    let recorder = mock
+        .builder()
+        .cassette(CassetteMode::Record(path.clone()))
+        .build()
+        .unwrap();
+    let recorded = recorder.get_profile("Twitter").await.unwrap();
+    drop(mock);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    // The guest token activation and the profile, one line each.
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    let player = TwitterScraper::builder()
+        .endpoints(endpoints)
+        .cassette(CassetteMode::Replay(path.clone()))
+        .build()
+        .unwrap();
+    let replayed = player.get_profile("Twitter").await.unwrap();
+    assert_eq!(recorded, replayed);
+
+    let missing = player.get_profile("elonmusk").await;
+    assert!(matches!(missing, Err(Error::CassetteMiss(_))));
+
+    fs::remove_file(path).unwrap();
+}
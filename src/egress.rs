@@ -0,0 +1,77 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use reqwest::Client;
+
+use crate::{rate_limit::RateLimits, token::GuestTokenCache};
+
+/// An HTTP client with the guest token activated through it and the budgets
+/// reported to it. Both are tied to the IP, so every proxy needs its own.
+pub(crate) struct Egress {
+    pub proxy: Option<String>,
+    pub client: Client,
+    pub guest_token: GuestTokenCache,
+    pub rate_limits: RateLimits,
+}
+
+/// Egresses used in turn, one per request.
+pub(crate) struct EgressPool {
+    egresses: Vec<Egress>,
+    next: AtomicUsize,
+}
+
+impl EgressPool {
+    pub fn new(egresses: Vec<Egress>) -> Self {
+        assert!(!egresses.is_empty(), "egress pool needs at least one client");
+        Self {
+            egresses,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn next(&self) -> &Egress {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.egresses[next % self.egresses.len()]
+    }
+
+    /// The next egress with budget left for `endpoint`, or the next one in
+    /// turn when all of them are exhausted.
+    pub fn next_for(&self, endpoint: &str) -> &Egress {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.egresses.len())
+            .map(|offset| &self.egresses[(start + offset) % self.egresses.len()])
+            .find(|egress| {
+                egress
+                    .rate_limits
+                    .get(endpoint)
+                    .is_none_or(|limit| !limit.is_exhausted())
+            })
+            .unwrap_or(&self.egresses[start % self.egresses.len()])
+    }
+
+    /// The first egress, whose guest token and budgets are saved with the session.
+    pub fn primary(&self) -> &Egress {
+        &self.egresses[0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Egress> {
+        self.egresses.iter()
+    }
+
+    /// Same clients and budgets with new, empty guest token caches.
+    pub fn with_guest_token_lifetime(&self, lifetime: Duration) -> Self {
+        Self::new(
+            self.egresses
+                .iter()
+                .map(|egress| Egress {
+                    proxy: egress.proxy.clone(),
+                    client: egress.client.clone(),
+                    guest_token: GuestTokenCache::new(lifetime),
+                    rate_limits: egress.rate_limits.duplicate(),
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,61 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    /// Host of the REST API, e.g. guest activation and profile timelines.
+    pub api: String,
+    /// Base URL of GraphQL operations.
+    pub graphql: String,
+    /// Host of the web client API used by search and trends.
+    pub web: String,
+}
+
+impl Endpoints {
+    /// Serves every endpoint from `base`, e.g. a local fixture server.
+    pub fn local<T: Into<String>>(base: T) -> Self {
+        let base = base.into().trim_end_matches('/').to_owned();
+        Self {
+            api: base.clone(),
+            graphql: format!("{}/graphql", base),
+            web: base,
+        }
+    }
+
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api, path)
+    }
+
+    pub fn graphql_url(&self, operation: &str) -> String {
+        format!("{}/{}", self.graphql, operation)
+    }
+
+    pub fn web_url(&self, path: &str) -> String {
+        format!("{}{}", self.web, path)
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            api: "https://api.twitter.com".to_owned(),
+            graphql: "https://api.twitter.com/graphql".to_owned(),
+            web: "https://twitter.com".to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_endpoint_urls() {
+    let endpoints = Endpoints::local("http://127.0.0.1:8080/");
+    assert_eq!(
+        endpoints.api_url("/1.1/guest/activate.json"),
+        "http://127.0.0.1:8080/1.1/guest/activate.json"
+    );
+    assert_eq!(
+        endpoints.graphql_url("KwJEsSEIHz991Ansf4Y1tQ/Followers"),
+        "http://127.0.0.1:8080/graphql/KwJEsSEIHz991Ansf4Y1tQ/Followers"
+    );
+    assert_eq!(
+        Endpoints::default().web_url("/i/api/2/guide.json"),
+        "https://twitter.com/i/api/2/guide.json"
+    );
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use chrono::ParseError;
 use reqwest::StatusCode;
 use serde::Deserialize;
@@ -13,19 +15,224 @@ pub enum Error {
     UserSuspended,
     UserNotFound,
     Unauthorized,
+    /// Credentials were missing, invalid or expired (codes 32, 89, 215, 353, 399).
+    AuthenticationFailed,
+    /// The authenticated account is suspended (code 64).
+    AccountSuspended,
+    /// The authenticated account is temporarily locked (code 326).
+    AccountLocked,
     UnauthorizedToViewSpecificUser,
     RateLimitExceeded,
+    ServerError(u16),
+    /// Twitter is over capacity (code 130).
+    OverCapacity,
     UserUnavailable,
+    TweetNotFound,
+    /// The tweet belongs to a protected account (code 179).
+    ProtectedTweet,
+    /// The page or endpoint does not exist (code 34).
+    PageNotFound,
+    /// The action was already performed, e.g. liking a liked tweet (code 139).
+    AlreadyPerformed,
+    InvalidGuestToken,
+    InvalidProxy(String),
+    InvalidHeader(String),
+    InvalidConfig(String),
+    IoError(std::io::Error),
+    CassetteMiss(String),
+    InvalidSession(String),
+    LoginFailed(String),
+    /// Every account of an [`crate::pool::AccountPool`] is quarantined.
+    NoAvailableAccount,
 
+    /// A response that could not be mapped to another variant.
     #[non_exhaustive]
-    UnknownError,
+    UnknownError {
+        status: Option<u16>,
+        code: Option<i32>,
+        message: Option<String>,
+        /// GraphQL path of the failing field, e.g. `user.result`.
+        path: Option<String>,
+        /// Response body, truncated to [`MAX_ERROR_BODY`] bytes.
+        body: Option<String>,
+    },
+}
+
+/// How much of an unexpected response body is kept in [`Error::UnknownError`].
+pub const MAX_ERROR_BODY: usize = 512;
+
+impl Error {
+    pub(crate) fn unknown() -> Self {
+        Self::UnknownError {
+            status: None,
+            code: None,
+            message: None,
+            path: None,
+            body: None,
+        }
+    }
+
+    /// Transient failures that may succeed when the request is sent again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ReqwestError(error) => error.is_timeout() || error.is_connect() || error.is_request(),
+            Self::RateLimitExceeded
+            | Self::ServerError(_)
+            | Self::OverCapacity
+            | Self::InvalidGuestToken
+            | Self::NoAvailableAccount => true,
+            _ => false,
+        }
+    }
+
+    /// Failures caused by the credentials or the account used, rather than the request.
+    pub fn is_auth(&self) -> bool {
+        matches!(
+            self,
+            Self::Unauthorized
+                | Self::AuthenticationFailed
+                | Self::LoginFailed(_)
+                | Self::AccountSuspended
+                | Self::AccountLocked
+                | Self::InvalidGuestToken
+        )
+    }
+
+    fn from_code(code: i32) -> Option<Self> {
+        let error = match code {
+            22 => Self::UnauthorizedToViewSpecificUser,
+            32 | 89 | 215 | 353 | 399 => Self::AuthenticationFailed,
+            34 => Self::PageNotFound,
+            37 => Self::Unauthorized,
+            50 => Self::UserNotFound,
+            63 => Self::UserSuspended,
+            64 => Self::AccountSuspended,
+            88 => Self::RateLimitExceeded,
+            130 | 131 => Self::OverCapacity,
+            139 => Self::AlreadyPerformed,
+            144 | 421 | 422 => Self::TweetNotFound,
+            179 => Self::ProtectedTweet,
+            200 | 239 => Self::InvalidGuestToken,
+            326 => Self::AccountLocked,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Attaches the response that caused an [`Error::UnknownError`].
+    pub(crate) fn with_response(self, status: StatusCode, response: &str) -> Self {
+        match self {
+            Self::UnknownError {
+                code,
+                message,
+                path,
+                ..
+            } => Self::UnknownError {
+                status: Some(status.as_u16()),
+                code,
+                message,
+                path,
+                body: Some(truncate(response, MAX_ERROR_BODY).to_owned()),
+            },
+            error => error,
+        }
+    }
+}
+
+/// Cuts `text` to at most `max` bytes, at a char boundary.
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let end = (0..=max).rev().find(|&end| text.is_char_boundary(end)).unwrap_or(0);
+    &text[..end]
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReqwestError(error) => write!(f, "request failed: {}", error),
+            Self::SerdeJsonError(error) => write!(f, "failed to parse response: {}", error),
+            Self::SerdeParamsError(error) => write!(f, "failed to encode parameters: {}", error),
+            Self::ParseError(error) => write!(f, "failed to parse date: {}", error),
+            Self::UserSuspended => write!(f, "user is suspended"),
+            Self::UserNotFound => write!(f, "user not found"),
+            Self::Unauthorized => write!(f, "not authorized"),
+            Self::AuthenticationFailed => write!(f, "authentication failed"),
+            Self::AccountSuspended => write!(f, "account is suspended"),
+            Self::AccountLocked => write!(f, "account is temporarily locked"),
+            Self::UnauthorizedToViewSpecificUser => write!(f, "not authorized to view this user"),
+            Self::RateLimitExceeded => write!(f, "rate limit exceeded"),
+            Self::ServerError(status) => write!(f, "server error (HTTP {})", status),
+            Self::OverCapacity => write!(f, "twitter is over capacity"),
+            Self::UserUnavailable => write!(f, "user is unavailable"),
+            Self::TweetNotFound => write!(f, "tweet not found"),
+            Self::ProtectedTweet => write!(f, "tweet is protected"),
+            Self::PageNotFound => write!(f, "page not found"),
+            Self::AlreadyPerformed => write!(f, "action was already performed"),
+            Self::InvalidGuestToken => write!(f, "guest token was rejected"),
+            Self::InvalidProxy(proxy) => write!(f, "invalid proxy: {}", proxy),
+            Self::InvalidHeader(header) => write!(f, "invalid header: {}", header),
+            Self::InvalidConfig(reason) => write!(f, "invalid configuration: {}", reason),
+            Self::IoError(error) => write!(f, "i/o error: {}", error),
+            Self::CassetteMiss(request) => write!(f, "no recorded response for {}", request),
+            Self::InvalidSession(reason) => write!(f, "invalid session: {}", reason),
+            Self::LoginFailed(reason) => write!(f, "login failed: {}", reason),
+            Self::NoAvailableAccount => write!(f, "no account is available"),
+            Self::UnknownError {
+                status,
+                code,
+                message,
+                path,
+                body,
+            } => {
+                write!(f, "unknown error")?;
+                if let Some(status) = status {
+                    write!(f, " (HTTP {})", status)?;
+                }
+                if let Some(code) = code {
+                    write!(f, ", code {}", code)?;
+                }
+                if let Some(message) = message {
+                    write!(f, ": {}", message)?;
+                } else if let Some(body) = body {
+                    write!(f, ": {}", body)?;
+                }
+                if let Some(path) = path {
+                    write!(f, " (at {})", path)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ReqwestError(error) => Some(error),
+            Self::SerdeJsonError(error) => Some(error),
+            Self::SerdeParamsError(error) => Some(error),
+            Self::ParseError(error) => Some(error),
+            Self::IoError(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 impl From<StatusCode> for Error {
     fn from(value: StatusCode) -> Self {
         match value.as_u16() {
+            401 => Self::AuthenticationFailed,
             429 => Self::RateLimitExceeded,
-            _ => Self::UnknownError,
+            500..=599 => Self::ServerError(value.as_u16()),
+            status => Self::UnknownError {
+                status: Some(status),
+                code: None,
+                message: None,
+                path: None,
+                body: None,
+            },
         }
     }
 }
@@ -35,7 +242,7 @@ impl From<reqwest::Error> for Error {
         if let Some(status) = error.status() {
             let error_from_status = Self::from(status);
             match error_from_status{
-                Self::UnknownError => (),
+                Self::UnknownError { .. } => (),
                 _ => return error_from_status
             }
         }
@@ -43,6 +250,12 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(error: serde_json::Error) -> Self {
         Self::SerdeJsonError(error)
@@ -66,22 +279,129 @@ pub struct ResponseError {
     pub errors: Vec<Message>,
 }
 
+/// An entry of the v1.1 or GraphQL `errors` array.
 #[derive(Deserialize, Debug)]
 pub struct Message {
+    #[serde(default)]
     pub code: i32,
+    #[serde(default)]
+    pub message: String,
+    /// GraphQL path of the failing field, e.g. `["user", "result"]`.
+    #[serde(default)]
+    pub path: Vec<serde_json::Value>,
+    pub extensions: Option<Extensions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Extensions {
+    pub code: Option<i32>,
+}
+
+impl Message {
+    /// The error code, which GraphQL errors may only carry in `extensions`.
+    pub fn error_code(&self) -> i32 {
+        match self.code {
+            0 => self
+                .extensions
+                .as_ref()
+                .and_then(|e| e.code)
+                .unwrap_or_default(),
+            code => code,
+        }
+    }
+
+    fn path(&self) -> Option<String> {
+        let path: Vec<String> = self
+            .path
+            .iter()
+            .map(|segment| match segment {
+                serde_json::Value::String(segment) => segment.to_owned(),
+                segment => segment.to_string(),
+            })
+            .collect();
+
+        (!path.is_empty()).then(|| path.join("."))
+    }
 }
 
 impl From<ResponseError> for Error {
+    /// Uses the first error with a known code, otherwise describes the first error.
     fn from(value: ResponseError) -> Self {
+        if let Some(error) = value
+            .errors
+            .iter()
+            .find_map(|msg| Self::from_code(msg.error_code()))
+        {
+            return error;
+        }
+
         match value.errors.first() {
-            Some(msg) => match msg.code {
-                22 => Self::UnauthorizedToViewSpecificUser,
-                37 => Self::Unauthorized,
-                50 => Self::UserNotFound,
-                63 => Self::UserSuspended,
-                _ => Self::UnknownError,
+            Some(msg) => Self::UnknownError {
+                status: None,
+                code: Some(msg.error_code()).filter(|code| *code != 0),
+                message: Some(msg.message.to_owned()).filter(|message| !message.is_empty()),
+                path: msg.path(),
+                body: None,
             },
-            None => Self::UnknownError,
+            None => Self::unknown(),
         }
     }
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_bad_guest_token_code() {
+    let response: ResponseError =
+        serde_json::from_str(r#"{"errors":[{"code":239,"message":"Bad guest token."}]}"#).unwrap();
+    assert!(matches!(Error::from(response), Error::InvalidGuestToken));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_unknown_error_is_diagnosable() {
+    let response: ResponseError =
+        serde_json::from_str(r#"{"errors":[{"code":999,"message":"Something odd."}]}"#).unwrap();
+    let body = "x".repeat(2 * MAX_ERROR_BODY);
+    let error = Error::from(response).with_response(StatusCode::BAD_REQUEST, &body);
+
+    match &error {
+        Error::UnknownError { status, code, body, .. } => {
+            assert_eq!(*status, Some(400));
+            assert_eq!(*code, Some(999));
+            assert_eq!(body.as_ref().map(|b| b.len()), Some(MAX_ERROR_BODY));
+        }
+        _ => panic!("expected UnknownError"),
+    }
+    assert_eq!(error.to_string(), "unknown error (HTTP 400), code 999: Something odd.");
+
+    let error: Box<dyn std::error::Error> = Box::new(Error::from(serde_json::from_str::<i32>("x").unwrap_err()));
+    assert!(error.source().is_some());
+
+    let multibyte = "é".repeat(MAX_ERROR_BODY);
+    assert_eq!(truncate(&multibyte, MAX_ERROR_BODY).len(), MAX_ERROR_BODY);
+    assert_eq!(truncate("aé", 2), "a");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_error_codes() {
+    let error = |body: &str| Error::from(serde_json::from_str::<ResponseError>(body).unwrap());
+
+    assert!(matches!(error(r#"{"errors":[{"code":88,"message":"Rate limit exceeded"}]}"#), Error::RateLimitExceeded));
+    assert!(matches!(error(r#"{"errors":[{"code":326,"message":"Locked"}]}"#), Error::AccountLocked));
+    assert!(matches!(error(r#"{"errors":[{"code":179,"message":"Protected"}]}"#), Error::ProtectedTweet));
+    assert!(error(r#"{"errors":[{"code":89,"message":"Invalid or expired token."}]}"#).is_auth());
+    assert!(error(r#"{"errors":[{"code":130,"message":"Over capacity"}]}"#).is_retryable());
+    assert!(!Error::UserNotFound.is_retryable());
+
+    let graphql = error(
+        r#"{"errors":[
+            {"message":"Something broke","path":["user","result",0]},
+            {"message":"Not found","path":["user"],"extensions":{"code":50}}
+        ]}"#,
+    );
+    assert!(matches!(graphql, Error::UserNotFound));
+
+    let graphql = error(r#"{"errors":[{"message":"Something broke","path":["user","result",0]}]}"#);
+    assert_eq!(graphql.to_string(), "unknown error: Something broke (at user.result.0)");
+}
@@ -6,4 +6,44 @@ pub mod types;
 pub mod scraper;
 pub use scraper::TwitterScraper;
 
-pub mod search;
\ No newline at end of file
+pub mod builder;
+pub use builder::TwitterScraperBuilder;
+
+pub mod endpoints;
+pub use endpoints::Endpoints;
+
+pub mod search;
+pub use search::SearchMode;
+
+pub mod cassette;
+pub use cassette::CassetteMode;
+
+pub mod rate_limit;
+pub use rate_limit::{RateLimit, RateLimitPolicy};
+
+pub mod retry;
+pub use retry::RetryPolicy;
+
+pub mod pacing;
+pub use pacing::Pacing;
+
+pub mod session;
+pub use session::{Cookie, Session};
+
+pub mod login;
+pub use login::LoginChallenge;
+
+pub mod pool;
+pub use pool::{AccountPool, PoolStrategy};
+
+mod egress;
+
+mod stream;
+
+mod token;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
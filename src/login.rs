@@ -0,0 +1,243 @@
+//! Username/password login through the `onboarding/task.json` flow.
+//!
+//! Every response names the subtasks Twitter wants completed next; the flow
+//! answers the first one until `LoginSuccessSubtask` is reached.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{Error, Result};
+
+/// Steps of the login flow, named after their `subtask_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginSubtask {
+    JsInstrumentation,
+    EnterUserIdentifier,
+    EnterAlternateIdentifier,
+    EnterPassword,
+    AccountDuplicationCheck,
+    /// Confirmation of the email address or phone number of the account.
+    Acid,
+    TwoFactorAuth,
+    Success,
+    Deny,
+    Unknown(String),
+}
+
+impl LoginSubtask {
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "LoginJsInstrumentationSubtask" => Self::JsInstrumentation,
+            "LoginEnterUserIdentifier" | "LoginEnterUserIdentifierSSO" => {
+                Self::EnterUserIdentifier
+            }
+            "LoginEnterAlternateIdentifierSubtask" => Self::EnterAlternateIdentifier,
+            "LoginEnterPassword" => Self::EnterPassword,
+            "AccountDuplicationCheck" => Self::AccountDuplicationCheck,
+            "LoginAcid" => Self::Acid,
+            "LoginTwoFactorAuthChallenge" => Self::TwoFactorAuth,
+            "LoginSuccessSubtask" => Self::Success,
+            "DenyLoginSubtask" => Self::Deny,
+            id => Self::Unknown(id.to_owned()),
+        }
+    }
+}
+
+/// Extra verification Twitter may ask for; answered by the caller's callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginChallenge {
+    /// Email address or phone number of the account, with Twitter's hint if any.
+    Acid { hint: Option<String> },
+    /// Email address or phone number when the username alone is ambiguous.
+    AlternateIdentifier { hint: Option<String> },
+    /// Code from an authenticator app or SMS.
+    TwoFactorAuth,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct FlowResponse {
+    pub flow_token: String,
+    #[serde(default)]
+    subtasks: Vec<FlowSubtask>,
+}
+
+#[derive(Deserialize, Debug)]
+struct FlowSubtask {
+    subtask_id: String,
+    enter_text: Option<EnterText>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EnterText {
+    hint_text: Option<String>,
+}
+
+impl FlowResponse {
+    pub fn next_subtask(&self) -> Option<(LoginSubtask, Option<String>)> {
+        self.subtasks.first().map(|subtask| {
+            (
+                LoginSubtask::from_id(&subtask.subtask_id),
+                subtask
+                    .enter_text
+                    .as_ref()
+                    .and_then(|text| text.hint_text.to_owned()),
+            )
+        })
+    }
+}
+
+/// Longest flow accepted before giving up, to avoid looping on repeated subtasks.
+const MAX_STEPS: usize = 20;
+
+pub(crate) struct LoginFlow<'a, F> {
+    username: &'a str,
+    password: &'a str,
+    challenge: F,
+    steps: usize,
+}
+
+impl<'a, F> LoginFlow<'a, F>
+where
+    F: FnMut(LoginChallenge) -> Option<String>,
+{
+    pub fn new(username: &'a str, password: &'a str, challenge: F) -> Self {
+        Self {
+            username,
+            password,
+            challenge,
+            steps: 0,
+        }
+    }
+
+    pub fn start_body() -> Value {
+        json!({
+            "input_flow_data": {
+                "flow_context": {
+                    "debug_overrides": {},
+                    "start_location": {"location": "splash_screen"}
+                }
+            },
+            "subtask_versions": {}
+        })
+    }
+
+    /// Body answering the next subtask of `response`, or `None` once logged in.
+    pub fn next(&mut self, response: &FlowResponse) -> Result<Option<Value>> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(Error::LoginFailed("too many login steps".to_owned()));
+        }
+
+        let (subtask, hint) = response
+            .next_subtask()
+            .ok_or_else(|| Error::LoginFailed("flow ended without success".to_owned()))?;
+
+        let input = match subtask {
+            LoginSubtask::Success => return Ok(None),
+            LoginSubtask::JsInstrumentation => json!({
+                "subtask_id": "LoginJsInstrumentationSubtask",
+                "js_instrumentation": {"response": "{}", "link": "next_link"}
+            }),
+            LoginSubtask::EnterUserIdentifier => json!({
+                "subtask_id": "LoginEnterUserIdentifierSSO",
+                "settings_list": {
+                    "setting_responses": [{
+                        "key": "user_identifier",
+                        "response_data": {"text_data": {"result": self.username}}
+                    }],
+                    "link": "next_link"
+                }
+            }),
+            LoginSubtask::EnterAlternateIdentifier => json!({
+                "subtask_id": "LoginEnterAlternateIdentifierSubtask",
+                "enter_text": {
+                    "text": self.answer(LoginChallenge::AlternateIdentifier { hint })?,
+                    "link": "next_link"
+                }
+            }),
+            LoginSubtask::EnterPassword => json!({
+                "subtask_id": "LoginEnterPassword",
+                "enter_password": {"password": self.password, "link": "next_link"}
+            }),
+            LoginSubtask::AccountDuplicationCheck => json!({
+                "subtask_id": "AccountDuplicationCheck",
+                "check_logged_in_account": {"link": "AccountDuplicationCheck_false"}
+            }),
+            LoginSubtask::Acid => json!({
+                "subtask_id": "LoginAcid",
+                "enter_text": {
+                    "text": self.answer(LoginChallenge::Acid { hint })?,
+                    "link": "next_link"
+                }
+            }),
+            LoginSubtask::TwoFactorAuth => json!({
+                "subtask_id": "LoginTwoFactorAuthChallenge",
+                "enter_text": {
+                    "text": self.answer(LoginChallenge::TwoFactorAuth)?,
+                    "link": "next_link"
+                }
+            }),
+            LoginSubtask::Deny => {
+                return Err(Error::LoginFailed("login denied".to_owned()));
+            }
+            LoginSubtask::Unknown(id) => {
+                return Err(Error::LoginFailed(format!("unsupported subtask {}", id)));
+            }
+        };
+
+        Ok(Some(json!({
+            "flow_token": response.flow_token,
+            "subtask_inputs": [input]
+        })))
+    }
+
+    fn answer(&mut self, challenge: LoginChallenge) -> Result<String> {
+        let description = format!("{:?}", challenge);
+        (self.challenge)(challenge)
+            .ok_or_else(|| Error::LoginFailed(format!("{} was not answered", description)))
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_login_flow() {
+    use crate::mock::{self, MockTwitter};
+
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
+
+    let mut challenges = vec![];
+    let session = scraper
+        .login("jack", mock::LOGIN_PASSWORD, |challenge| {
+            challenges.push(challenge);
+            Some(mock::LOGIN_EMAIL.to_owned())
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        challenges,
+        vec![LoginChallenge::Acid {
+            hint: Some("Phone or email".to_owned())
+        }]
+    );
+    assert_eq!(session.get("auth_token"), Some("mock-auth"));
+    assert_eq!(session.csrf_token(), Some("mock-csrf"));
+    assert_eq!(session.get("guest_id"), Some("v1%3A1"));
+
+    scraper.logout().await.unwrap();
+    assert!(scraper.session().is_none());
+
+    let wrong_password = scraper.login("jack", "hunter2", |_| None).await;
+    assert!(matches!(wrong_password, Err(Error::AuthenticationFailed)));
+    assert!(scraper.session().is_none());
+
+    let unanswered = scraper.login("jack", mock::LOGIN_PASSWORD, |_| None).await;
+    assert!(matches!(unanswered, Err(Error::LoginFailed(_))));
+
+    let old = crate::Session::from_cookie_header("auth_token=old; ct0=old").unwrap();
+    let scraper = mock.builder().session(old.clone()).build().unwrap();
+    let clone = scraper.clone();
+    let wrong_password = scraper.login("jack", "hunter2", |_| None).await;
+    assert!(matches!(wrong_password, Err(Error::AuthenticationFailed)));
+    assert_eq!(clone.session(), Some(old));
+}
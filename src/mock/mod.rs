@@ -0,0 +1,221 @@
+//! Local stand-in for the Twitter API serving recorded fixtures.
+//!
+//! Known screen names: `Twitter` resolves to a profile with followers and
+//! following; `protected` (code 22), `unauthorized` (37), `not_found` (50),
+//! `suspended` (63) and `rate_limited` (HTTP 429) produce errors.
+//!
+//! The login flow accepts any username with [`LOGIN_PASSWORD`], asks for email
+//! confirmation (`LoginAcid`) and expects [`LOGIN_EMAIL`]; other passwords fail
+//! with code 399.
+
+use wiremock::{
+    matchers::{body_partial_json, header, header_exists, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use serde_json::json;
+
+use crate::{Endpoints, TwitterScraper, TwitterScraperBuilder};
+
+pub const GUEST_ACTIVATE: &str = include_str!("fixtures/guest_activate.json");
+pub const USER_BY_SCREEN_NAME: &str = include_str!("fixtures/user_by_screen_name.json");
+pub const FOLLOWERS: &str = include_str!("fixtures/followers.json");
+pub const FOLLOWING: &str = include_str!("fixtures/following.json");
+pub const SEARCH: &str = include_str!("fixtures/search.json");
+pub const SEARCH_USERS: &str = include_str!("fixtures/search_users.json");
+pub const TWEET_DETAIL: &str = include_str!("fixtures/tweet_detail.json");
+pub const GUIDE: &str = include_str!("fixtures/guide.json");
+pub const ERROR_22: &str = include_str!("fixtures/error_22.json");
+pub const ERROR_37: &str = include_str!("fixtures/error_37.json");
+pub const ERROR_50: &str = include_str!("fixtures/error_50.json");
+pub const ERROR_63: &str = include_str!("fixtures/error_63.json");
+pub const ERROR_399: &str = include_str!("fixtures/error_399.json");
+pub const LOGIN_JS_INSTRUMENTATION: &str = include_str!("fixtures/login_js_instrumentation.json");
+pub const LOGIN_ENTER_USER_IDENTIFIER: &str =
+    include_str!("fixtures/login_enter_user_identifier.json");
+pub const LOGIN_ENTER_PASSWORD: &str = include_str!("fixtures/login_enter_password.json");
+pub const LOGIN_ACCOUNT_DUPLICATION_CHECK: &str =
+    include_str!("fixtures/login_account_duplication_check.json");
+pub const LOGIN_ACID: &str = include_str!("fixtures/login_acid.json");
+pub const LOGIN_SUCCESS: &str = include_str!("fixtures/login_success.json");
+
+pub const LOGIN_PASSWORD: &str = "correct horse battery staple";
+pub const LOGIN_EMAIL: &str = "jack@example.com";
+
+pub const GUEST_ACTIVATE_PATH: &str = "/1.1/guest/activate.json";
+pub const USER_BY_SCREEN_NAME_PATH: &str = "/graphql/4S2ihIKfF3xhp-ENxvUAfQ/UserByScreenName";
+pub const FOLLOWERS_PATH: &str = "/graphql/KwJEsSEIHz991Ansf4Y1tQ/Followers";
+pub const FOLLOWING_PATH: &str = "/graphql/cocC_CzoxzpwgXr3jhG7DA/Following";
+pub const TWEET_DETAIL_PATH: &str = "/graphql/VWFGPVAGkZMGRKGe3GFFnA/TweetDetail";
+pub const SEARCH_PATH: &str = "/i/api/2/search/adaptive.json";
+pub const GUIDE_PATH: &str = "/i/api/2/guide.json";
+pub const ONBOARDING_TASK_PATH: &str = "/1.1/onboarding/task.json";
+pub const LOGOUT_PATH: &str = "/1.1/account/logout.json";
+
+pub struct MockTwitter {
+    server: MockServer,
+}
+
+impl MockTwitter {
+    /// Starts a server with every bundled fixture mounted.
+    pub async fn start() -> Self {
+        let mock = Self::empty().await;
+        mock.mount_fixtures().await;
+        mock
+    }
+
+    /// Starts a server without fixtures, for tests that mount their own.
+    pub async fn empty() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints::local(self.server.uri())
+    }
+
+    pub fn builder(&self) -> TwitterScraperBuilder {
+        TwitterScraper::builder().endpoints(self.endpoints())
+    }
+
+    pub fn scraper(&self) -> TwitterScraper {
+        self.builder()
+            .build()
+            .expect("mock scraper configuration is valid")
+    }
+
+    pub async fn mount(&self, mock: Mock) {
+        mock.mount(&self.server).await
+    }
+
+    async fn mount_fixtures(&self) {
+        self.mount(
+            Mock::given(method("POST"))
+                .and(path(GUEST_ACTIVATE_PATH))
+                .respond_with(json(200, GUEST_ACTIVATE)),
+        )
+        .await;
+
+        self.mount_profile("Twitter", json(200, USER_BY_SCREEN_NAME))
+            .await;
+        self.mount_profile("protected", json(401, ERROR_22)).await;
+        self.mount_profile("unauthorized", json(403, ERROR_37)).await;
+        self.mount_profile("not_found", json(404, ERROR_50)).await;
+        self.mount_profile("suspended", json(403, ERROR_63)).await;
+        self.mount_profile("rate_limited", ResponseTemplate::new(429))
+            .await;
+
+        for (route, body) in [
+            (FOLLOWERS_PATH, FOLLOWERS),
+            (FOLLOWING_PATH, FOLLOWING),
+            (TWEET_DETAIL_PATH, TWEET_DETAIL),
+            (GUIDE_PATH, GUIDE),
+        ] {
+            self.mount(
+                Mock::given(method("GET"))
+                    .and(path(route))
+                    .and(header_exists("x-guest-token"))
+                    .respond_with(json(200, body)),
+            )
+            .await;
+        }
+
+        self.mount(
+            Mock::given(method("GET"))
+                .and(path(SEARCH_PATH))
+                .and(query_param("result_filter", "user"))
+                .and(header_exists("x-guest-token"))
+                .respond_with(json(200, SEARCH_USERS))
+                .with_priority(1),
+        )
+        .await;
+        self.mount(
+            Mock::given(method("GET"))
+                .and(path(SEARCH_PATH))
+                .and(header_exists("x-guest-token"))
+                .respond_with(json(200, SEARCH)),
+        )
+        .await;
+
+        self.mount_login().await;
+    }
+
+    async fn mount_login(&self) {
+        self.mount(
+            Mock::given(method("POST"))
+                .and(path(ONBOARDING_TASK_PATH))
+                .and(query_param("flow_name", "login"))
+                .and(header_exists("x-guest-token"))
+                .respond_with(
+                    json(200, LOGIN_JS_INSTRUMENTATION)
+                        .insert_header("att", "1-mock")
+                        .insert_header("set-cookie", "guest_id=v1%3A1; Domain=.twitter.com; Path=/"),
+                ),
+        )
+        .await;
+
+        let steps = [
+            (json!({"subtask_id": "LoginJsInstrumentationSubtask"}), json(200, LOGIN_ENTER_USER_IDENTIFIER)),
+            (json!({"subtask_id": "LoginEnterUserIdentifierSSO"}), json(200, LOGIN_ENTER_PASSWORD)),
+            (
+                json!({"enter_password": {"password": LOGIN_PASSWORD}}),
+                json(200, LOGIN_ACCOUNT_DUPLICATION_CHECK),
+            ),
+            (json!({"subtask_id": "LoginEnterPassword"}), json(400, ERROR_399)),
+            (json!({"subtask_id": "AccountDuplicationCheck"}), json(200, LOGIN_ACID)),
+            (
+                json!({"enter_text": {"text": LOGIN_EMAIL}}),
+                json(200, LOGIN_SUCCESS)
+                    .append_header("set-cookie", "auth_token=mock-auth; Domain=.twitter.com; Path=/; Secure; HttpOnly")
+                    .append_header("set-cookie", "ct0=mock-csrf; Domain=.twitter.com; Path=/; Secure"),
+            ),
+        ];
+        for (priority, (input, response)) in steps.into_iter().enumerate() {
+            self.mount(
+                Mock::given(method("POST"))
+                    .and(path(ONBOARDING_TASK_PATH))
+                    .and(header("att", "1-mock"))
+                    .and(header_exists("x-guest-token"))
+                    .and(body_partial_json(json!({"subtask_inputs": [input]})))
+                    .respond_with(response)
+                    .with_priority(priority as u8 + 1),
+            )
+            .await;
+        }
+
+        self.mount(
+            Mock::given(method("POST"))
+                .and(path(LOGOUT_PATH))
+                .and(header("x-csrf-token", "mock-csrf"))
+                .respond_with(
+                    json(200, r#"{"status":"ok"}"#)
+                        .insert_header("set-cookie", "auth_token=; Max-Age=0; Domain=.twitter.com; Path=/"),
+                ),
+        )
+        .await;
+    }
+
+    async fn mount_profile(&self, screen_name: &str, response: ResponseTemplate) {
+        let variables = format!(
+            r#"{{"screen_name":"{}","withHighlightedLabel":true}}"#,
+            screen_name
+        );
+        self.mount(
+            Mock::given(method("GET"))
+                .and(path(USER_BY_SCREEN_NAME_PATH))
+                .and(query_param("variables", variables))
+                .and(header_exists("x-guest-token"))
+                .respond_with(response),
+        )
+        .await;
+    }
+}
+
+pub fn json(status: u16, body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(body, "application/json")
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::Url;
+
+/// Limits how fast requests are sent. Clones of a scraper share one pace.
+#[derive(Debug, Clone, Default)]
+pub struct Pacing {
+    min_delay: Option<Duration>,
+    rate: Option<(u32, Duration)>,
+    endpoints: HashMap<String, (u32, Duration)>,
+}
+
+impl Pacing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Minimum time between the start of two requests.
+    pub fn min_delay(mut self, delay: Duration) -> Self {
+        self.min_delay = Some(delay);
+        self
+    }
+
+    /// At most `requests` per `window` across all endpoints, allowing bursts up to `requests`.
+    pub fn rate(mut self, requests: u32, window: Duration) -> Self {
+        self.rate = Some((requests, window));
+        self
+    }
+
+    /// At most `requests` per `window` to `endpoint` (a URL path, e.g.
+    /// `/i/api/2/search/adaptive.json`), on top of the global pace.
+    pub fn endpoint_rate<T: Into<String>>(
+        mut self,
+        endpoint: T,
+        requests: u32,
+        window: Duration,
+    ) -> Self {
+        self.endpoints.insert(endpoint.into(), (requests, window));
+        self
+    }
+}
+
+struct TokenBucket {
+    capacity: f64,
+    /// Tokens added per second.
+    refill: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests: u32, window: Duration) -> Self {
+        let capacity = requests.max(1) as f64;
+        Self {
+            capacity,
+            refill: capacity / window.as_secs_f64().max(f64::EPSILON),
+            tokens: capacity,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Time until a token is available.
+    fn wait(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill).min(self.capacity);
+        self.updated = now;
+
+        match self.tokens >= 1.0 {
+            true => Duration::ZERO,
+            // A long enough window can put the wait past what a Duration holds.
+            false => Duration::try_from_secs_f64((1.0 - self.tokens) / self.refill)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+struct PacerState {
+    last: Option<Instant>,
+    global: Option<TokenBucket>,
+    endpoints: HashMap<String, TokenBucket>,
+}
+
+pub(crate) struct Pacer {
+    min_delay: Option<Duration>,
+    state: Mutex<PacerState>,
+}
+
+impl Pacer {
+    pub fn new(pacing: Pacing) -> Self {
+        let endpoints = pacing
+            .endpoints
+            .into_iter()
+            .map(|(endpoint, (requests, window))| (endpoint, TokenBucket::new(requests, window)))
+            .collect();
+
+        Self {
+            min_delay: pacing.min_delay,
+            state: Mutex::new(PacerState {
+                last: None,
+                global: pacing
+                    .rate
+                    .map(|(requests, window)| TokenBucket::new(requests, window)),
+                endpoints,
+            }),
+        }
+    }
+
+    /// Waits until a request to `url` fits every configured limit and reserves it.
+    pub async fn acquire(&self, url: &Url) {
+        loop {
+            let wait = self.try_acquire(url.path(), Instant::now());
+            if wait.is_zero() {
+                return;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn try_acquire(&self, endpoint: &str, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let delay = match (self.min_delay, state.last) {
+            (Some(min_delay), Some(last)) => last
+                .checked_add(min_delay)
+                .map_or(min_delay, |next| next.saturating_duration_since(now)),
+            _ => Duration::ZERO,
+        };
+        let global = state.global.as_mut().map(|b| b.wait(now)).unwrap_or_default();
+        let mut endpoint = state.endpoints.get_mut(endpoint);
+        let local = endpoint.as_mut().map(|b| b.wait(now)).unwrap_or_default();
+
+        let wait = delay.max(global).max(local);
+        if wait.is_zero() {
+            state.last = Some(now);
+            if let Some(bucket) = state.global.as_mut() {
+                bucket.take();
+            }
+            if let Some(bucket) = endpoint {
+                bucket.take();
+            }
+        }
+        wait
+    }
+}
+
+impl Default for Pacer {
+    fn default() -> Self {
+        Self::new(Pacing::default())
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_pacing() {
+    let search = Url::parse("https://twitter.com/i/api/2/search/adaptive.json").unwrap();
+    let profile = Url::parse("https://twitter.com/graphql/x/UserByScreenName").unwrap();
+
+    let pacer = Pacer::new(Pacing::new().min_delay(Duration::from_millis(20)));
+    let start = Instant::now();
+    for _ in 0..3 {
+        pacer.acquire(&search).await;
+    }
+    assert!(start.elapsed() >= Duration::from_millis(40));
+
+    let pacer = Pacer::new(
+        Pacing::new()
+            .rate(10, Duration::from_secs(60))
+            .endpoint_rate("/i/api/2/search/adaptive.json", 2, Duration::from_secs(60)),
+    );
+    let now = Instant::now();
+    assert!(pacer.try_acquire(search.path(), now).is_zero());
+    assert!(pacer.try_acquire(search.path(), now).is_zero());
+    assert!(!pacer.try_acquire(search.path(), now).is_zero());
+    assert!(pacer.try_acquire(profile.path(), now).is_zero());
+
+    // Waits too long to add to an Instant or to hold in a Duration.
+    let pacer = Pacer::new(Pacing::new().min_delay(Duration::MAX).rate(1, Duration::MAX));
+    assert!(pacer.try_acquire(search.path(), now).is_zero());
+    assert_eq!(pacer.try_acquire(search.path(), now), Duration::MAX);
+}
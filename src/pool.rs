@@ -0,0 +1,387 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use futures::Stream;
+
+use crate::{
+    session::Session,
+    stream::paginate_user,
+    types::{profile::Profile, tweet::Tweet},
+    Error, Result, SearchMode, TwitterScraper,
+};
+
+/// Rate-limit windows are 15 minutes, so a limited account is rested that long by default.
+const DEFAULT_QUARANTINE: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolStrategy {
+    /// Cycle through the available accounts in order.
+    #[default]
+    RoundRobin,
+    /// Prefer the account that was rate limited longest ago, or never.
+    LeastRecentlyLimited,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountHealth {
+    pub name: String,
+    pub requests: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    /// Time since the account was last rate limited.
+    pub limited_ago: Option<Duration>,
+    /// Time left before a quarantined account is used again.
+    pub quarantined_for: Option<Duration>,
+}
+
+impl AccountHealth {
+    pub fn is_available(&self) -> bool {
+        self.quarantined_for.is_none()
+    }
+}
+
+#[derive(Default)]
+struct AccountState {
+    requests: u64,
+    failures: u64,
+    last_error: Option<String>,
+    last_used: Option<Instant>,
+    last_limited: Option<Instant>,
+    /// When the quarantine started and how long it lasts. Kept apart, since a
+    /// long quarantine can overflow an `Instant`.
+    quarantined: Option<(Instant, Duration)>,
+}
+
+impl AccountState {
+    fn is_available(&self, now: Instant) -> bool {
+        self.quarantined_for(now).is_none()
+    }
+
+    fn quarantined_for(&self, now: Instant) -> Option<Duration> {
+        let (since, quarantine) = self.quarantined?;
+        Some(quarantine.saturating_sub(now.saturating_duration_since(since)))
+            .filter(|left| !left.is_zero())
+    }
+}
+
+struct Account {
+    name: String,
+    scraper: TwitterScraper,
+    state: Mutex<AccountState>,
+}
+
+/// Spreads requests over several accounts, resting the ones that get rate
+/// limited or lose their authorization.
+pub struct AccountPool {
+    accounts: Vec<Account>,
+    strategy: PoolStrategy,
+    quarantine: Duration,
+    next: AtomicUsize,
+}
+
+impl AccountPool {
+    pub fn new() -> Self {
+        Self {
+            accounts: vec![],
+            strategy: PoolStrategy::default(),
+            quarantine: DEFAULT_QUARANTINE,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn strategy(mut self, strategy: PoolStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// How long an account is skipped after a quarantining error.
+    pub fn quarantine(mut self, quarantine: Duration) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    /// Adds an account using `scraper`, which may be a guest or carry a session.
+    pub fn account<T: Into<String>>(mut self, name: T, scraper: TwitterScraper) -> Self {
+        self.accounts.push(Account {
+            name: name.into(),
+            scraper,
+            state: Mutex::new(AccountState::default()),
+        });
+        self
+    }
+
+    /// Adds a logged-in account with default scraper settings.
+    pub fn session<T: Into<String>>(self, name: T, session: Session) -> Self {
+        self.account(name, TwitterScraper::new().with_session(session))
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn health(&self) -> Vec<AccountHealth> {
+        let now = Instant::now();
+        self.accounts
+            .iter()
+            .map(|account| {
+                let state = account.state.lock().unwrap();
+                AccountHealth {
+                    name: account.name.to_owned(),
+                    requests: state.requests,
+                    failures: state.failures,
+                    last_error: state.last_error.to_owned(),
+                    limited_ago: state.last_limited.map(|limited| now - limited),
+                    quarantined_for: state.quarantined_for(now),
+                }
+            })
+            .collect()
+    }
+
+    /// Runs `request` with an available account. When the account gets
+    /// quarantined the request is repeated with the next one.
+    pub async fn execute<F, Fut, T>(&self, request: F) -> Result<T>
+    where
+        F: Fn(TwitterScraper) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for _ in 0..self.accounts.len() {
+            let account = match self.pick() {
+                Some(account) => account,
+                None => break,
+            };
+
+            match request(account.scraper.clone()).await {
+                Err(error) if self.record_error(account, &error) => last_error = Some(error),
+                result => return result,
+            }
+        }
+
+        Err(last_error.unwrap_or(Error::NoAvailableAccount))
+    }
+
+    pub async fn get_profile(&self, username: &str) -> Result<Profile> {
+        self.execute(|scraper| async move { scraper.get_profile(username).await })
+            .await
+    }
+
+    pub async fn get_users_tweets(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        self.execute(|scraper| {
+            let cursor = cursor.clone();
+            async move { scraper.get_users_tweets(username, cursor).await }
+        })
+        .await
+    }
+
+    pub async fn search(
+        &self,
+        query: &str,
+        mode: SearchMode,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        self.execute(|scraper| {
+            let cursor = cursor.clone();
+            async move { scraper.search(query, mode, cursor).await }
+        })
+        .await
+    }
+
+    pub async fn get_followers(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.execute(|scraper| {
+            let cursor = cursor.clone();
+            async move { scraper.get_followers(username, cursor).await }
+        })
+        .await
+    }
+
+    pub async fn get_following(
+        &self,
+        username: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.execute(|scraper| {
+            let cursor = cursor.clone();
+            async move { scraper.get_following(username, cursor).await }
+        })
+        .await
+    }
+
+    pub fn followers_stream<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate_user(
+            self.user_id(username),
+            move |user_id, cursor| async move {
+                self.get_follower_following_by_id(&user_id, false, cursor)
+                    .await
+            },
+            limit,
+        )
+    }
+
+    pub fn following_stream<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate_user(
+            self.user_id(username),
+            move |user_id, cursor| async move {
+                self.get_follower_following_by_id(&user_id, true, cursor)
+                    .await
+            },
+            limit,
+        )
+    }
+
+    async fn user_id(&self, username: &str) -> Result<String> {
+        self.execute(|scraper| async move { scraper.user_id(username).await })
+            .await
+    }
+
+    async fn get_follower_following_by_id(
+        &self,
+        user_id: &str,
+        following: bool,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        self.execute(|scraper| {
+            let cursor = cursor.clone();
+            async move {
+                scraper
+                    .get_follower_following_by_id(user_id, following, cursor)
+                    .await
+            }
+        })
+        .await
+    }
+
+    fn pick(&self) -> Option<&Account> {
+        let now = Instant::now();
+        let account = match self.strategy {
+            PoolStrategy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..self.accounts.len())
+                    .map(|offset| &self.accounts[(start + offset) % self.accounts.len()])
+                    .find(|account| account.state.lock().unwrap().is_available(now))
+            }
+            PoolStrategy::LeastRecentlyLimited => self
+                .accounts
+                .iter()
+                .filter(|account| account.state.lock().unwrap().is_available(now))
+                .min_by_key(|account| {
+                    let state = account.state.lock().unwrap();
+                    (state.last_limited, state.last_used)
+                }),
+        }?;
+
+        let mut state = account.state.lock().unwrap();
+        state.requests += 1;
+        state.last_used = Some(now);
+        drop(state);
+        Some(account)
+    }
+
+    /// Updates the health of `account`; returns whether it was quarantined.
+    fn record_error(&self, account: &Account, error: &Error) -> bool {
+        let now = Instant::now();
+        let mut state = account.state.lock().unwrap();
+        state.failures += 1;
+        state.last_error = Some(error.to_string());
+        if matches!(error, Error::RateLimitExceeded) {
+            state.last_limited = Some(now);
+        }
+
+        let quarantine = matches!(
+            error,
+            Error::RateLimitExceeded
+                | Error::Unauthorized
+                | Error::AuthenticationFailed
+                | Error::AccountLocked
+                | Error::AccountSuspended
+        );
+        if quarantine {
+            state.quarantined = Some((now, self.quarantine));
+        }
+        quarantine
+    }
+}
+
+impl Default for AccountPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_account_pool_rotation() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, ResponseTemplate,
+    };
+
+    let mock = MockTwitter::start().await;
+    mock.mount(
+        Mock::given(method("GET"))
+            .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+            .and(header("cookie", "auth_token=limited"))
+            .respond_with(ResponseTemplate::new(429))
+            .with_priority(1),
+    )
+    .await;
+
+    let limited = Session::from_cookie_header("auth_token=limited").unwrap();
+    let pool = AccountPool::new()
+        .account("limited", mock.builder().session(limited).build().unwrap())
+        .account("guest", mock.scraper());
+
+    let profile = pool.get_profile("Twitter").await.unwrap();
+    assert_eq!(profile.username, "Twitter");
+    pool.get_profile("Twitter").await.unwrap();
+
+    let health = pool.health();
+    assert!(!health[0].is_available());
+    assert_eq!((health[0].requests, health[0].failures), (1, 1));
+    assert!(health[0].limited_ago.is_some());
+    assert!(health[1].is_available());
+    assert_eq!((health[1].requests, health[1].failures), (2, 0));
+
+    // A quarantine too long to add to an Instant.
+    let pool = AccountPool::new()
+        .strategy(PoolStrategy::LeastRecentlyLimited)
+        .quarantine(Duration::MAX)
+        .account(
+            "limited",
+            mock.builder()
+                .session(Session::from_cookie_header("auth_token=limited").unwrap())
+                .build()
+                .unwrap(),
+        );
+    let exceeded = pool.get_profile("Twitter").await;
+    assert!(matches!(exceeded, Err(Error::RateLimitExceeded)));
+    let exhausted = pool.get_profile("Twitter").await;
+    assert!(matches!(exhausted, Err(Error::NoAvailableAccount)));
+    assert!(pool.health()[0].quarantined_for.is_some());
+}
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+
+use chrono::Utc;
+use reqwest::{header::HeaderMap, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// What to do before a request to an endpoint whose budget is used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitPolicy {
+    /// Send the request anyway and let Twitter answer with 429.
+    #[default]
+    Ignore,
+    /// Sleep until the window resets.
+    Wait,
+    /// Return [`Error::RateLimitExceeded`] without sending the request.
+    FailFast,
+}
+
+/// Budget reported by the `x-rate-limit-*` headers of the last response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Unix timestamp (seconds) at which the window resets.
+    pub reset: i64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok();
+
+        Some(Self {
+            limit: header("x-rate-limit-limit")?.parse::<u32>().ok()?,
+            remaining: header("x-rate-limit-remaining")?.parse::<u32>().ok()?,
+            reset: header("x-rate-limit-reset")?.parse::<i64>().ok()?,
+        })
+    }
+
+    /// Budget summed over several egresses, resetting when the first window does.
+    pub(crate) fn combine<I: IntoIterator<Item = RateLimit>>(limits: I) -> Option<Self> {
+        limits.into_iter().reduce(|total, limit| Self {
+            limit: total.limit.saturating_add(limit.limit),
+            remaining: total.remaining.saturating_add(limit.remaining),
+            reset: total.reset.min(limit.reset),
+        })
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && self.reset_in() > Duration::ZERO
+    }
+
+    /// Time left until the window resets.
+    pub fn reset_in(&self) -> Duration {
+        Duration::from_secs((self.reset - Utc::now().timestamp()).max(0) as u64)
+    }
+}
+
+/// Budgets keyed by endpoint path, one set per egress.
+pub(crate) struct RateLimits {
+    limits: RwLock<HashMap<String, RateLimit>>,
+    policy: RateLimitPolicy,
+}
+
+impl RateLimits {
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        Self {
+            limits: RwLock::new(HashMap::new()),
+            policy,
+        }
+    }
+
+    /// Same policy and budgets, tracked separately from now on.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            limits: RwLock::new(self.all()),
+            policy: self.policy,
+        }
+    }
+
+    pub fn get(&self, endpoint: &str) -> Option<RateLimit> {
+        self.limits.read().unwrap().get(endpoint).copied()
+    }
+
+    pub fn all(&self) -> HashMap<String, RateLimit> {
+        self.limits.read().unwrap().clone()
+    }
+
+    /// Restores budgets saved earlier, keeping the ones already known.
+    pub fn restore(&self, limits: HashMap<String, RateLimit>) {
+        let mut current = self.limits.write().unwrap();
+        for (endpoint, limit) in limits {
+            current.entry(endpoint).or_insert(limit);
+        }
+    }
+
+    pub fn update(&self, url: &Url, headers: &HeaderMap) {
+        if let Some(limit) = RateLimit::from_headers(headers) {
+            self.limits
+                .write()
+                .unwrap()
+                .insert(url.path().to_owned(), limit);
+        }
+    }
+
+    /// Applies the policy before a request to `url` is sent.
+    pub async fn acquire(&self, url: &Url) -> Result<()> {
+        let limit = match self.get(url.path()) {
+            Some(limit) if limit.is_exhausted() => limit,
+            _ => return Ok(()),
+        };
+
+        match self.policy {
+            RateLimitPolicy::Ignore => Ok(()),
+            RateLimitPolicy::FailFast => Err(Error::RateLimitExceeded),
+            RateLimitPolicy::Wait => {
+                tokio::time::sleep(limit.reset_in()).await;
+                self.limits.write().unwrap().remove(url.path());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self::new(RateLimitPolicy::default())
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_rate_limit_fail_fast() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{method, path},
+        Mock,
+    };
+
+    let reset = (Utc::now().timestamp() + 60).to_string();
+    let mock = MockTwitter::empty().await;
+    mock.mount(
+        Mock::given(method("POST"))
+            .and(path(mock::GUEST_ACTIVATE_PATH))
+            .respond_with(mock::json(200, mock::GUEST_ACTIVATE)),
+    )
+    .await;
+    mock.mount(
+        Mock::given(method("GET"))
+            .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+            .respond_with(
+                mock::json(200, mock::USER_BY_SCREEN_NAME)
+                    .insert_header("x-rate-limit-limit", "95")
+                    .insert_header("x-rate-limit-remaining", "0")
+                    .insert_header("x-rate-limit-reset", reset.as_str()),
+            ),
+    )
+    .await;
+
+    let scraper = mock
+        .builder()
+        .rate_limit_policy(RateLimitPolicy::FailFast)
+        .build()
+        .unwrap();
+    assert!(scraper.get_profile("Twitter").await.is_ok());
+
+    let limit = scraper.rate_limit(mock::USER_BY_SCREEN_NAME_PATH).unwrap();
+    assert_eq!(limit.limit, 95);
+    assert!(limit.is_exhausted());
+
+    let exceeded = scraper.get_profile("Twitter").await;
+    assert!(matches!(exceeded, Err(Error::RateLimitExceeded)));
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-rate-limit-limit", "95".parse().unwrap());
+    headers.insert("x-rate-limit-remaining", "-1".parse().unwrap());
+    headers.insert("x-rate-limit-reset", reset.parse().unwrap());
+    assert_eq!(RateLimit::from_headers(&headers), None);
+    headers.insert("x-rate-limit-remaining", "4294967296".parse().unwrap());
+    assert_eq!(RateLimit::from_headers(&headers), None);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_rate_limit_per_proxy() {
+    use crate::{
+        mock::{self, MockTwitter},
+        Endpoints, TwitterScraper,
+    };
+    use wiremock::{
+        matchers::{method, path},
+        Mock,
+    };
+
+    let reset = (Utc::now().timestamp() + 60).to_string();
+    let mut proxies = vec![];
+    for (remaining, requests) in [("0", 1), ("50", 3)] {
+        let proxy = MockTwitter::empty().await;
+        proxy
+            .mount(
+                Mock::given(method("POST"))
+                    .and(path(mock::GUEST_ACTIVATE_PATH))
+                    .respond_with(mock::json(200, mock::GUEST_ACTIVATE)),
+            )
+            .await;
+        proxy
+            .mount(
+                Mock::given(method("GET"))
+                    .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+                    .respond_with(
+                        mock::json(200, mock::USER_BY_SCREEN_NAME)
+                            .insert_header("x-rate-limit-limit", "95")
+                            .insert_header("x-rate-limit-remaining", remaining)
+                            .insert_header("x-rate-limit-reset", reset.as_str()),
+                    )
+                    .expect(requests),
+            )
+            .await;
+        proxies.push(proxy);
+    }
+
+    let scraper = TwitterScraper::builder()
+        .endpoints(Endpoints::local("http://twitter.test"))
+        .proxies(proxies.iter().map(|proxy| proxy.server().uri()))
+        .rate_limit_policy(RateLimitPolicy::FailFast)
+        .build()
+        .unwrap();
+    for _ in 0..4 {
+        scraper.get_profile("Twitter").await.unwrap();
+    }
+
+    let limit = scraper.rate_limit(mock::USER_BY_SCREEN_NAME_PATH).unwrap();
+    assert_eq!((limit.limit, limit.remaining), (190, 50));
+    assert!(!limit.is_exhausted());
+}
@@ -0,0 +1,180 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use crate::Error;
+
+type Retryable = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+type OnRetry = Arc<dyn Fn(u32, &Error, Duration) + Send + Sync>;
+
+/// How failed requests are retried. The default policy sends every request once.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retryable: Retryable,
+    on_retry: Option<OnRetry>,
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` attempts with exponential backoff starting at 500ms.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            retryable: Arc::new(is_transient),
+            on_retry: None,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Growth of the delay between attempts. Values below 1.0 or not finite are ignored.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        if multiplier.is_finite() && multiplier >= 1.0 {
+            self.multiplier = multiplier;
+        }
+        self
+    }
+
+    /// Fraction of each delay that is randomized, between 0 (fixed delays) and 1.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        if !jitter.is_nan() {
+            self.jitter = jitter.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// Replaces the default check, which retries [`Error::is_retryable`] errors
+    /// except rate limits and rejected guest tokens, as those are handled by
+    /// [`crate::RateLimitPolicy`] and the guest token refresh.
+    pub fn retry_if<F>(mut self, retryable: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// Called with the failed attempt number, its error and the delay before the next one.
+    pub fn on_retry<F>(mut self, on_retry: F) -> Self
+    where
+        F: Fn(u32, &Error, Duration) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(on_retry));
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Delay after failed attempt `attempt` (starting at 1), or `None` when
+    /// `error` should be returned to the caller.
+    pub(crate) fn next_delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(self.retryable)(error) {
+            return None;
+        }
+
+        // Clamped as seconds, since the exponential can overflow a Duration.
+        let exponent = (attempt - 1).min(i32::MAX as u32) as i32;
+        let delay = (self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_backoff.as_secs_f64());
+        let delay = Duration::from_secs_f64(delay * (1.0 - self.jitter * fastrand::f64()));
+
+        if let Some(on_retry) = &self.on_retry {
+            on_retry(attempt, error, delay);
+        }
+        Some(delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_transient(error: &Error) -> bool {
+    error.is_retryable() && !matches!(error, Error::RateLimitExceeded | Error::InvalidGuestToken)
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_retry_transient_errors() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    let mock = MockTwitter::start().await;
+    mock.mount(
+        Mock::given(method("GET"))
+            .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .with_priority(1),
+    )
+    .await;
+
+    let retries = Arc::new(AtomicU32::new(0));
+    let counter = retries.clone();
+    let policy = RetryPolicy::new(3)
+        .backoff(Duration::ZERO, Duration::ZERO)
+        .on_retry(move |_, error, _| {
+            assert!(matches!(error, Error::ServerError(503)));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+    let scraper = mock.builder().retry_policy(policy).build().unwrap();
+
+    assert!(scraper.get_profile("Twitter").await.is_ok());
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+
+    let not_found = scraper.get_profile("not_found").await;
+    assert!(matches!(not_found, Err(Error::UserNotFound)));
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_retry_backoff_is_bounded() {
+    let policy = RetryPolicy::new(u32::MAX).multiplier(-1.0).multiplier(f64::NAN);
+    assert_eq!(policy.multiplier, 2.0);
+
+    let max = Duration::from_secs(30);
+    for attempt in [1, 100, 2000, u32::MAX - 1] {
+        let delay = policy.next_delay(attempt, &Error::ServerError(503)).unwrap();
+        assert!(delay <= max);
+    }
+    assert!(policy.next_delay(u32::MAX, &Error::ServerError(503)).is_none());
+
+    let fixed = RetryPolicy::new(1000).jitter(0.0);
+    assert_eq!(fixed.next_delay(999, &Error::ServerError(503)), Some(max));
+}
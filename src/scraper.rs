@@ -1,127 +1,463 @@
-use std::cell::RefCell;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use super::types::auth::GuestToken;
 use crate::{
+    builder::TwitterScraperBuilder,
+    cassette::{Cassette, RawResponse},
+    egress::{Egress, EgressPool},
+    endpoints::Endpoints,
+    login::{FlowResponse, LoginChallenge, LoginFlow},
     error::{ResponseError, Error},
+    pacing::{Pacer, Pacing},
+    rate_limit::RateLimit,
+    retry::RetryPolicy,
+    session::{Cookie, Session, SessionFile, SESSION_FILE_VERSION},
+    stream::{paginate, paginate_user},
     types::{
-        adaptive::AdaptiveParams, auth::CSRFAuth, graph::GraphResponse, params::Params,
-        profile::{TwitterUserResponse, Profile}, timeline::TwitterTimelineResponse, tweet::Tweet,
+        adaptive::{AdaptiveParams, GuideParams}, auth::CSRFAuth,
+        conversation::{Conversation, TweetDetailResponse}, graph::GraphResponse,
+        params::{Params, TweetDetailParams}, profile::{TwitterUserResponse, Profile}, timeline::TwitterTimelineResponse, trend::Trend, tweet::Tweet,
     },
+    search::SearchMode,
     Result,
 };
 use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    Client, ClientBuilder, Method,
+    header::{HeaderMap, HeaderValue, COOKIE, SET_COOKIE},
+    Method, Url,
 };
+use futures::Stream;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
-const BEARER_TOKEN: &str = "Bearer AAAAAAAAAAAAAAAAAAAAAPYXBAAAAAAACLXUNDekMxqa8h%2F40K4moUkGsoc%3DTYfbDKbT3jJPCEVnMYqilB28NHfOPqkca3qaAxGfsyKCs0wRbw";
+pub(crate) const BEARER_TOKEN: &str = "Bearer AAAAAAAAAAAAAAAAAAAAAPYXBAAAAAAACLXUNDekMxqa8h%2F40K4moUkGsoc%3DTYfbDKbT3jJPCEVnMYqilB28NHfOPqkca3qaAxGfsyKCs0wRbw";
 
+#[derive(Clone)]
 pub struct TwitterScraper {
-    client: Client,
-    guest_token: RefCell<Option<GuestToken>>,
-    csrf_auth: Option<CSRFAuth>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) endpoints: Endpoints,
+    pub(crate) egress: Arc<EgressPool>,
+    pub(crate) session: Arc<RwLock<Option<Session>>>,
+    pub(crate) cassette: Option<Arc<Cassette>>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) pacer: Arc<Pacer>,
 }
 
 impl TwitterScraper {
     pub fn new() -> Self {
-        let client = Self::get_reqwest_client();
+        Self::builder()
+            .build()
+            .expect("default scraper configuration is valid")
+    }
 
-        Self {
-            client,
-            guest_token: None.into(),
-            csrf_auth: None,
-        }
+    pub fn builder() -> TwitterScraperBuilder {
+        TwitterScraperBuilder::new()
     }
 
-    pub fn add_csrf_auth<T: Into<String>>(mut self, auth_token: T, csrf_token: T) -> Self {
-        self.csrf_auth = Some(CSRFAuth {
+    pub fn add_csrf_auth<T: Into<String>>(self, auth_token: T, csrf_token: T) -> Self {
+        self.with_session(Session::from(CSRFAuth {
             auth_token: auth_token.into(),
             csrf_token: csrf_token.into(),
-        });
+        }))
+    }
+
+    /// Sends the cookies of `session` with every request. Unlike
+    /// [`TwitterScraper::set_session`], this does not affect existing clones.
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Arc::new(RwLock::new(Some(session)));
         self
     }
 
-    pub async fn get_guest_token(&self) -> Result<()> {
-        let guest_token = self
-            .make_request(
-                "https://api.twitter.com/1.1/guest/activate.json",
-                Method::POST,
-                &None
+    /// Replaces the session of this scraper and all of its clones.
+    pub fn set_session(&self, session: Option<Session>) {
+        *self.session.write().unwrap() = session;
+    }
+
+    pub fn session(&self) -> Option<Session> {
+        self.session.read().unwrap().clone()
+    }
+
+    pub fn with_guest_token_lifetime(mut self, lifetime: Duration) -> Self {
+        self.egress = Arc::new(self.egress.with_guest_token_lifetime(lifetime));
+        self
+    }
+
+    /// Budget reported for `endpoint` (the URL path, e.g.
+    /// `/i/api/2/search/adaptive.json`) by its last response, summed over all
+    /// proxies since each one has its own.
+    pub fn rate_limit(&self, endpoint: &str) -> Option<RateLimit> {
+        RateLimit::combine(
+            self.egress
+                .iter()
+                .filter_map(|egress| egress.rate_limits.get(endpoint)),
+        )
+    }
+
+    pub fn rate_limits(&self) -> HashMap<String, RateLimit> {
+        let mut limits: HashMap<String, Vec<RateLimit>> = HashMap::new();
+        for egress in self.egress.iter() {
+            for (endpoint, limit) in egress.rate_limits.all() {
+                limits.entry(endpoint).or_default().push(limit);
+            }
+        }
+        limits
+            .into_iter()
+            .filter_map(|(endpoint, limits)| Some((endpoint, RateLimit::combine(limits)?)))
+            .collect()
+    }
+
+    /// Waits at least `delay` between requests, replacing any configured pacing.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.pacer = Arc::new(Pacer::new(Pacing::new().min_delay(delay)));
+        self
+    }
+
+    /// Proxies requests are spread over; `None` stands for a direct connection.
+    pub fn proxies(&self) -> Vec<Option<String>> {
+        self.egress.iter().map(|egress| egress.proxy.clone()).collect()
+    }
+
+    /// Saves the guest token, cookies and rate-limit budgets to `path`, readable
+    /// by the current user only. With several proxies, only the guest token and
+    /// budgets of the first one are saved.
+    pub fn save_session<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let session = self.session();
+        SessionFile {
+            version: SESSION_FILE_VERSION,
+            guest_token: self.egress.primary().guest_token.get(),
+            csrf_token: session
+                .as_ref()
+                .and_then(|s| s.csrf_token())
+                .map(str::to_owned),
+            session,
+            rate_limits: self.egress.primary().rate_limits.all(),
+        }
+        .write(path.as_ref())
+    }
+
+    /// Restores state written by [`TwitterScraper::save_session`]. An expired guest
+    /// token is replaced on the next request as usual.
+    pub fn load_session<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = SessionFile::read(path.as_ref())?;
+
+        if let Some(guest_token) = file.guest_token {
+            self.egress.primary().guest_token.set(guest_token);
+        }
+        let mut session = file.session;
+        if let Some(csrf_token) = file.csrf_token {
+            let session = session.get_or_insert_with(Session::new);
+            if session.csrf_token().is_none() {
+                session.insert(Cookie::new(String::from("ct0"), csrf_token));
+            }
+        }
+        if session.is_some() {
+            self.set_session(session);
+        }
+        self.egress.primary().rate_limits.restore(file.rate_limits);
+        Ok(())
+    }
+
+    /// Logs in with `username` and `password` and uses the resulting session for
+    /// every request. `challenge` answers extra verification steps such as email
+    /// confirmation or two-factor codes; returning `None` aborts the login.
+    pub async fn login<F>(&self, username: &str, password: &str, challenge: F) -> Result<Session>
+    where
+        F: FnMut(LoginChallenge) -> Option<String> + Send,
+    {
+        let session = self.run_login(username, password, challenge).await?;
+        self.set_session(Some(session.clone()));
+        Ok(session)
+    }
+
+    async fn run_login<F>(&self, username: &str, password: &str, challenge: F) -> Result<Session>
+    where
+        F: FnMut(LoginChallenge) -> Option<String> + Send,
+    {
+        // Every step has to come from the same IP as the guest token.
+        let egress = self.egress.next();
+        // Cookies of the flow are kept apart until it succeeds, so clones keep
+        // using the current session meanwhile.
+        let mut session = Session::new();
+        let mut flow = LoginFlow::new(username, password, challenge);
+        let mut att = None;
+        let mut response = self
+            .send_login_task(
+                egress,
+                self.endpoints.api_url("/1.1/onboarding/task.json?flow_name=login"),
+                &LoginFlow::<F>::start_body(),
+                &mut att,
+                &mut session,
             )
             .await?;
 
-        self.guest_token.replace(Some(guest_token));
+        while let Some(body) = flow.next(&response)? {
+            response = self
+                .send_login_task(
+                    egress,
+                    self.endpoints.api_url("/1.1/onboarding/task.json"),
+                    &body,
+                    &mut att,
+                    &mut session,
+                )
+                .await?;
+        }
+
+        match session.is_authenticated() {
+            true => Ok(session),
+            false => Err(Error::LoginFailed("no auth_token cookie was set".to_owned())),
+        }
+    }
+
+    /// Posts one step of the login flow, echoing the `att` header Twitter expects.
+    async fn send_login_task(
+        &self,
+        egress: &Egress,
+        url: String,
+        body: &Value,
+        att: &mut Option<HeaderValue>,
+        session: &mut Session,
+    ) -> Result<FlowResponse> {
+        let guest_token = self.current_guest_token(egress).await?;
+        let mut headers = guest_token_headers(Some(&guest_token))?;
+        if let Some(att) = att.as_ref() {
+            headers.insert("att", att.clone());
+        }
+
+        let response = self
+            .send_raw(url, Method::POST, egress, Some(body), headers, Some(session))
+            .await?;
+        if let Some(value) = response.headers.get("att") {
+            *att = Some(value.clone());
+        }
+        parse_response(response)
+    }
+
+    /// Ends the session on Twitter's side and stops sending its cookies.
+    pub async fn logout(&self) -> Result<()> {
+        let result = self
+            .send_raw(
+                self.endpoints.api_url("/1.1/account/logout.json"),
+                Method::POST,
+                self.egress.next(),
+                None,
+                HeaderMap::new(),
+                None,
+            )
+            .await;
+        self.set_session(None);
+
+        let response = result?;
+        match response.status.is_success() {
+            true => Ok(()),
+            false => parse_response::<Value>(response).map(|_| ()),
+        }
+    }
+
+    /// Activates a guest token through every configured proxy.
+    pub async fn get_guest_token(&self) -> Result<()> {
+        for egress in self.egress.iter() {
+            let guest_token = self.activate_guest_token(egress).await?;
+            egress.guest_token.set(guest_token);
+        }
         Ok(())
     }
 
-    fn get_reqwest_client() -> Client {
-        let mut headers = HeaderMap::new();
-        headers.append("Authorization", HeaderValue::from_static(BEARER_TOKEN));
+    async fn activate_guest_token(&self, egress: &Egress) -> Result<GuestToken> {
+        self.send_request(
+            self.endpoints.api_url("/1.1/guest/activate.json"),
+            Method::POST,
+            egress,
+            None,
+        )
+        .await
+    }
 
-        ClientBuilder::new()
-            .default_headers(headers)
-            .build()
-            .unwrap()
+    /// Returns the cached guest token of `egress`, activating a new one if there
+    /// is none yet or it has expired.
+    async fn current_guest_token(&self, egress: &Egress) -> Result<GuestToken> {
+        match egress.guest_token.valid() {
+            Some(token) => Ok(token),
+            None => self.refresh_guest_token(egress, None).await,
+        }
     }
-    async fn make_request<S, T>(&self, url: S, method: Method, csrf: &Option<CSRFAuth>) -> Result<T>
+
+    /// Activates a new guest token unless another task already replaced `stale`.
+    async fn refresh_guest_token(
+        &self,
+        egress: &Egress,
+        stale: Option<&GuestToken>,
+    ) -> Result<GuestToken> {
+        let _refresh = egress.guest_token.lock_refresh().await;
+        if let Some(token) = egress.guest_token.valid() {
+            if stale.is_none_or(|stale| stale.guest_token != token.guest_token) {
+                return Ok(token);
+            }
+        }
+
+        let token = self.activate_guest_token(egress).await?;
+        egress.guest_token.set(token.clone());
+        Ok(token)
+    }
+
+    async fn make_request<S, T>(&self, url: S, method: Method) -> Result<T>
     where
         S: reqwest::IntoUrl,
         T: DeserializeOwned + 'static,
     {
-        let req = self.client.request(method, url);
+        let url = url.into_url()?;
+        let mut attempt = 1;
+        loop {
+            match self.make_request_once(url.clone(), method.clone()).await {
+                Err(error) => match self.retry_policy.next_delay(attempt, &error) {
+                    Some(delay) => {
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(error),
+                },
+                result => return result,
+            }
+        }
+    }
 
-        let req = match &self.guest_token.take() {
-            Some(token) => req.header(
-                "X-Guest-Token",
-                HeaderValue::from_str(token.guest_token.as_str()).unwrap(),
-            ),
-            None => req,
+    async fn make_request_once<T>(&self, url: Url, method: Method) -> Result<T>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let egress = self.egress.next_for(url.path());
+        let guest_token = self.current_guest_token(egress).await?;
+
+        match self
+            .send_request(url.clone(), method.clone(), egress, Some(&guest_token))
+            .await
+        {
+            Err(Error::InvalidGuestToken) => {
+                let guest_token = self.refresh_guest_token(egress, Some(&guest_token)).await?;
+                self.send_request(url, method, egress, Some(&guest_token))
+                    .await
+            }
+            result => result,
+        }
+    }
+    async fn send_request<S, T>(
+        &self,
+        url: S,
+        method: Method,
+        egress: &Egress,
+        guest_token: Option<&GuestToken>,
+    ) -> Result<T>
+    where
+        S: reqwest::IntoUrl,
+        T: DeserializeOwned + 'static,
+    {
+        let headers = guest_token_headers(guest_token)?;
+        let response = self
+            .send_raw(url, method, egress, None, headers, None)
+            .await?;
+        parse_response(response)
+    }
+
+    /// Sends a request with the cookies of `jar`, or of the scraper's session
+    /// when `None`, and stores the cookies set by the response there.
+    async fn send_raw<S>(
+        &self,
+        url: S,
+        method: Method,
+        egress: &Egress,
+        body: Option<&Value>,
+        mut headers: HeaderMap,
+        jar: Option<&mut Session>,
+    ) -> Result<RawResponse>
+    where
+        S: reqwest::IntoUrl,
+    {
+        let url = url.into_url()?;
+        egress.rate_limits.acquire(&url).await?;
+
+        let response = match &self.cassette {
+            Some(cassette) if cassette.is_replay() => cassette.replay(&method, &url)?,
+            _ => {
+                let session = match &jar {
+                    Some(jar) => Some((*jar).clone()),
+                    None => self.session(),
+                };
+                if let Some(session) = session {
+                    if let Some(cookies) = session.cookie_header() {
+                        headers.insert(COOKIE, header_value(&cookies)?);
+                    }
+                    if let Some(csrf_token) = session.csrf_token() {
+                        headers.insert("x-csrf-token", header_value(csrf_token)?);
+                    }
+                }
+                let response = self
+                    .execute(url.clone(), method.clone(), egress, body, headers)
+                    .await?;
+                if let Some(cassette) = &self.cassette {
+                    cassette.record(&method, &url, &response)?;
+                }
+                response
+            }
         };
 
-        let req = match &csrf {
-            Some(token) => req
-                .header(
-                    "cookie",
-                    HeaderValue::from_str(
-                        format!("auth_token={};ct0={}", token.auth_token, token.csrf_token)
-                            .as_str(),
-                    )
-                    .unwrap(),
-                )
-                .header(
-                    "x-csrf-token",
-                    HeaderValue::from_str(token.csrf_token.as_str()).unwrap(),
-                ),
-            None => req,
+        egress.rate_limits.update(&url, &response.headers);
+        let apply = |session: &mut Session| {
+            for cookie in response.headers.get_all(SET_COOKIE) {
+                if let Ok(cookie) = cookie.to_str() {
+                    session.apply_set_cookie(cookie);
+                }
+            }
         };
-        let req = req.send().await?;
-        let code = req.status();
-        let response = req.text().await?;
+        match jar {
+            Some(jar) => apply(jar),
+            None => {
+                if let Some(session) = self.session.write().unwrap().as_mut() {
+                    apply(session);
+                }
+            }
+        }
+        Ok(response)
+    }
 
-        if code.as_u16() != 200 {
-            let response_error: ResponseError =
-                serde_json::from_str(&response).map_err(|_| Error::from(code))?;
+    async fn execute(
+        &self,
+        url: Url,
+        method: Method,
+        egress: &Egress,
+        body: Option<&Value>,
+        headers: HeaderMap,
+    ) -> Result<RawResponse> {
+        self.pacer.acquire(&url).await;
 
-            return Err(response_error.into());
-        }
+        let req = egress
+            .client
+            .request(method, url)
+            .headers(self.headers.clone())
+            .headers(headers);
+        let req = match body {
+            Some(body) => req.json(body),
+            None => req,
+        };
 
-        match serde_json::from_str(&response) {
-            Ok(t) => Ok(t),
-            Err(error) => {
-                println!("{}, {}", response, code);
-                let response_error: ResponseError =
-                    serde_json::from_str(&response).map_err(|_| error)?;
+        let req = req.send().await?;
+        let status = req.status();
+        let headers = req.headers().clone();
+        let body = req.text().await?;
 
-                Err(response_error.into())
-            }
-        }
+        Ok(RawResponse {
+            status,
+            headers,
+            body,
+        })
     }
     async fn get_timeline_response<S>(&self, url: S) -> Result<TwitterTimelineResponse>
     where
         S: reqwest::IntoUrl,
     {
-        self.make_request(url, Method::GET, &None).await
+        self.make_request(url, Method::GET).await
     }
 
     pub async fn get_users_tweets(
@@ -129,13 +465,21 @@ impl TwitterScraper {
         username: &str,
         cursor: Option<String>,
     ) -> Result<(Vec<Tweet>, Option<String>)> {
-        let user_id = self.get_profile(username).await?.user_id;
+        let user_id = self.user_id(username).await?;
+        self.get_users_tweets_by_id(&user_id, cursor).await
+    }
 
-        let params = AdaptiveParams::user_tweets_params(&user_id, cursor);
+    pub(crate) async fn get_users_tweets_by_id(
+        &self,
+        user_id: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        let params = AdaptiveParams::user_tweets_params(user_id, cursor);
 
         let url = format!(
-            "https://api.twitter.com/2/timeline/profile/{}.json?{}",
-            user_id,
+            "{}?{}",
+            self.endpoints
+                .api_url(&format!("/2/timeline/profile/{}.json", user_id)),
             serde_url_params::to_string(&params)?
         );
         
@@ -147,11 +491,13 @@ impl TwitterScraper {
     pub async fn search(
         &self,
         query: &str,
+        mode: SearchMode,
         cursor: Option<String>,
     ) -> Result<(Vec<Tweet>, Option<String>)> {
-        let params = AdaptiveParams::search_params(query, cursor);
+        let params = AdaptiveParams::search_params(query, mode, cursor);
         let url = format!(
-            "https://twitter.com/i/api/2/search/adaptive.json?{}",
+            "{}?{}",
+            self.endpoints.web_url("/i/api/2/search/adaptive.json"),
             serde_url_params::to_string(&params)?
         );
         self.get_timeline_response(url)
@@ -159,12 +505,94 @@ impl TwitterScraper {
             .map(|x| x.parse_tweets())
     }
 
+    pub async fn search_profiles(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        let params = AdaptiveParams::search_params(query, SearchMode::People, cursor);
+        let url = format!(
+            "{}?{}",
+            self.endpoints.web_url("/i/api/2/search/adaptive.json"),
+            serde_url_params::to_string(&params)?
+        );
+        self.get_timeline_response(url)
+            .await
+            .map(|x| x.parse_users())
+    }
+
+    pub async fn get_trends(&self) -> Result<Vec<Trend>> {
+        self.get_trends_response(None).await
+    }
+
+    pub async fn get_trends_for_location(&self, woeid: i64) -> Result<Vec<Trend>> {
+        self.get_trends_response(Some(woeid)).await
+    }
+
+    async fn get_trends_response(&self, woeid: Option<i64>) -> Result<Vec<Trend>> {
+        let params = GuideParams::trends_params(woeid);
+        let url = format!(
+            "{}?{}",
+            self.endpoints.web_url("/i/api/2/guide.json"),
+            serde_url_params::to_string(&params)?
+        );
+        self.get_timeline_response(url)
+            .await
+            .map(|x| x.parse_trends())
+    }
+
     pub async fn get_profile(&self, username: &str) -> Result<Profile> {
-        let url = format!("https://api.twitter.com/graphql/4S2ihIKfF3xhp-ENxvUAfQ/UserByScreenName?variables=%7B%22screen_name%22%3A%22{}%22%2C%22withHighlightedLabel%22%3Atrue%7D", username);
-        let response: TwitterUserResponse = self.make_request(url, Method::GET, &None).await?;
+        let url = format!("{}?variables=%7B%22screen_name%22%3A%22{}%22%2C%22withHighlightedLabel%22%3Atrue%7D", self.endpoints.graphql_url("4S2ihIKfF3xhp-ENxvUAfQ/UserByScreenName"), username);
+        let response: TwitterUserResponse = self.make_request(url, Method::GET).await?;
         response.try_into()
     }
 
+    pub async fn get_tweet(&self, id: &str) -> Result<Tweet> {
+        self.get_conversation(id)
+            .await
+            .map(|(conversation, _)| conversation.focal)
+    }
+
+    /// The focal tweet, its ancestors and the first page of replies, with the
+    /// cursor to pass to [`Self::get_more_replies`].
+    pub async fn get_conversation(&self, id: &str) -> Result<(Conversation, Option<String>)> {
+        let (tweets, cursor) = self.get_tweet_detail(id, None).await?;
+
+        Conversation::build(id, tweets)
+            .map(|conversation| (conversation, cursor))
+            .ok_or(Error::TweetNotFound)
+    }
+
+    /// Adds the page of replies at `cursor` to `conversation` and returns the
+    /// cursor of the next page.
+    pub async fn get_more_replies(
+        &self,
+        conversation: &mut Conversation,
+        cursor: String,
+    ) -> Result<Option<String>> {
+        let id = conversation.focal.id.to_string();
+        let (tweets, cursor) = self.get_tweet_detail(&id, Some(cursor)).await?;
+        conversation.extend(tweets);
+        Ok(cursor)
+    }
+
+    async fn get_tweet_detail(
+        &self,
+        id: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Tweet>, Option<String>)> {
+        let params = TweetDetailParams::new(id.to_owned(), cursor).to_url()?;
+        let url = format!(
+            "{}?{}",
+            self.endpoints.graphql_url("VWFGPVAGkZMGRKGe3GFFnA/TweetDetail"),
+            params
+        );
+
+        self.make_request::<_, TweetDetailResponse>(url, Method::GET)
+            .await
+            .map(|r| r.parse_tweets())
+    }
+
     pub async fn get_followers(
         &self,
         username: &str,
@@ -181,21 +609,94 @@ impl TwitterScraper {
         self.get_follower_following(username, true, cursor).await
     }
 
+    pub fn tweets_stream<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate_user(
+            self.user_id(username),
+            move |user_id, cursor| async move {
+                self.get_users_tweets_by_id(&user_id, cursor).await
+            },
+            limit,
+        )
+    }
+
+    pub fn search_stream<'a>(
+        &'a self,
+        query: &'a str,
+        mode: SearchMode,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate(move |cursor| self.search(query, mode, cursor), limit)
+    }
+
+    pub fn search_profiles_stream<'a>(
+        &'a self,
+        query: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate(move |cursor| self.search_profiles(query, cursor), limit)
+    }
+
+    pub fn followers_stream<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate_user(
+            self.user_id(username),
+            move |user_id, cursor| async move {
+                self.get_follower_following_by_id(&user_id, false, cursor).await
+            },
+            limit,
+        )
+    }
+
+    pub fn following_stream<'a>(
+        &'a self,
+        username: &'a str,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate_user(
+            self.user_id(username),
+            move |user_id, cursor| async move {
+                self.get_follower_following_by_id(&user_id, true, cursor).await
+            },
+            limit,
+        )
+    }
+
+    pub(crate) async fn user_id(&self, username: &str) -> Result<String> {
+        Ok(self.get_profile(username).await?.user_id)
+    }
+
     async fn get_follower_following(
         &self,
         username: &str,
         following: bool,
         cursor: Option<String>,
     ) -> Result<(Vec<Profile>, Option<String>)> {
-        let user_id = self.get_profile(username).await?.user_id;
-        let params = Params::new(user_id, cursor).to_url()?;
+        let user_id = self.user_id(username).await?;
+        self.get_follower_following_by_id(&user_id, following, cursor)
+            .await
+    }
+
+    pub(crate) async fn get_follower_following_by_id(
+        &self,
+        user_id: &str,
+        following: bool,
+        cursor: Option<String>,
+    ) -> Result<(Vec<Profile>, Option<String>)> {
+        let params = Params::new(user_id.to_owned(), cursor).to_url()?;
         let url = match following {
-            true => "https://api.twitter.com/graphql/cocC_CzoxzpwgXr3jhG7DA/Following",
-            false => "https://api.twitter.com/graphql/KwJEsSEIHz991Ansf4Y1tQ/Followers",
+            true => self.endpoints.graphql_url("cocC_CzoxzpwgXr3jhG7DA/Following"),
+            false => self.endpoints.graphql_url("KwJEsSEIHz991Ansf4Y1tQ/Followers"),
         };
         let url = format!("{}?{}", url, params);
 
-        self.make_request::<_, GraphResponse>(url, Method::GET, &self.csrf_auth)
+        self.make_request::<_, GraphResponse>(url, Method::GET)
             .await
             .map(|r| r.get_users())?
     }
@@ -206,3 +707,230 @@ impl Default for TwitterScraper {
         Self::new()
     }
 }
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(value.to_owned()))
+}
+
+fn guest_token_headers(guest_token: Option<&GuestToken>) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = guest_token {
+        headers.insert("x-guest-token", header_value(&token.guest_token)?);
+    }
+    Ok(headers)
+}
+
+fn parse_response<T: DeserializeOwned>(response: RawResponse) -> Result<T> {
+    if response.status.as_u16() != 200 {
+        let error = match serde_json::from_str::<ResponseError>(&response.body) {
+            Ok(response_error) => Error::from(response_error),
+            Err(_) => Error::from(response.status),
+        };
+
+        return Err(error.with_response(response.status, &response.body));
+    }
+
+    match serde_json::from_str(&response.body) {
+        Ok(t) => Ok(t),
+        Err(error) => {
+            let response_error: ResponseError =
+                serde_json::from_str(&response.body).map_err(|_| error)?;
+
+            Err(Error::from(response_error).with_response(response.status, &response.body))
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_scraper_is_shareable() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    fn assert_send<T: Send>(_: T) {}
+
+    let scraper = Arc::new(TwitterScraper::new());
+    assert_send_sync(&scraper);
+    assert_send(scraper.get_profile("Twitter"));
+    assert_send(scraper.search("bitcoin", SearchMode::Latest, None));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_guest_token_refreshed_when_rejected() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock,
+    };
+
+    let mock = MockTwitter::empty().await;
+    mock.mount(
+        Mock::given(method("POST"))
+            .and(path(mock::GUEST_ACTIVATE_PATH))
+            .respond_with(mock::json(200, r#"{"guest_token":"stale"}"#))
+            .up_to_n_times(1),
+    )
+    .await;
+    mock.mount(
+        Mock::given(method("POST"))
+            .and(path(mock::GUEST_ACTIVATE_PATH))
+            .respond_with(mock::json(200, r#"{"guest_token":"fresh"}"#)),
+    )
+    .await;
+    mock.mount(
+        Mock::given(method("GET"))
+            .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+            .and(header("x-guest-token", "stale"))
+            .respond_with(mock::json(403, r#"{"errors":[{"code":239,"message":"Bad guest token."}]}"#)),
+    )
+    .await;
+    mock.mount(
+        Mock::given(method("GET"))
+            .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+            .and(header("x-guest-token", "fresh"))
+            .respond_with(mock::json(200, mock::USER_BY_SCREEN_NAME)),
+    )
+    .await;
+
+    let profile = mock.scraper().get_profile("Twitter").await.unwrap();
+    assert_eq!(profile.username, "Twitter");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_session_sent_to_every_endpoint() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock,
+    };
+
+    let mock = MockTwitter::start().await;
+    for (route, body) in [
+        (mock::SEARCH_PATH, mock::SEARCH),
+        (mock::USER_BY_SCREEN_NAME_PATH, mock::USER_BY_SCREEN_NAME),
+        (mock::TWEET_DETAIL_PATH, mock::TWEET_DETAIL),
+        (mock::FOLLOWERS_PATH, mock::FOLLOWERS),
+    ] {
+        mock.mount(
+            Mock::given(method("GET"))
+                .and(path(route))
+                .and(header("cookie", "auth_token=abc; ct0=def"))
+                .and(header("x-csrf-token", "def"))
+                .respond_with(mock::json(200, body))
+                .with_priority(1)
+                .expect(1..),
+        )
+        .await;
+    }
+
+    let session = Session::from_cookie_header("auth_token=abc; ct0=def").unwrap();
+    let scraper = mock.builder().session(session).build().unwrap();
+    scraper.search("bitcoin", SearchMode::Latest, None).await.unwrap();
+    scraper.get_profile("Twitter").await.unwrap();
+    scraper.get_tweet("2").await.unwrap();
+    scraper.get_followers("Twitter", None).await.unwrap();
+
+    let clone = scraper.clone();
+    scraper.set_session(None);
+    assert!(clone.session().is_none());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_session_saved_and_restored() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock,
+    };
+
+    let file = std::env::temp_dir().join(format!("twitter-session-{}.json", std::process::id()));
+
+    let mock = MockTwitter::start().await;
+    let scraper = mock.builder().csrf_auth("abc", "def").build().unwrap();
+    scraper.get_profile("Twitter").await.unwrap();
+
+    // An existing, world-readable file is replaced with an owner-only one.
+    std::fs::write(&file, "").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+    }
+    scraper.save_session(&file).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let restarted = MockTwitter::empty().await;
+    restarted
+        .mount(
+            Mock::given(method("POST"))
+                .and(path(mock::GUEST_ACTIVATE_PATH))
+                .respond_with(mock::json(200, mock::GUEST_ACTIVATE))
+                .expect(0),
+        )
+        .await;
+    restarted
+        .mount(
+            Mock::given(method("GET"))
+                .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+                .and(header("x-csrf-token", "def"))
+                .respond_with(mock::json(200, mock::USER_BY_SCREEN_NAME)),
+        )
+        .await;
+
+    let scraper = restarted.scraper();
+    scraper.load_session(&file).unwrap();
+    assert!(scraper.session().unwrap().is_authenticated());
+    scraper.get_profile("Twitter").await.unwrap();
+
+    std::fs::remove_file(file).unwrap();
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_guest_token_per_proxy() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock,
+    };
+
+    let mut proxies = vec![];
+    for token in ["first", "second"] {
+        let proxy = MockTwitter::empty().await;
+        proxy
+            .mount(
+                Mock::given(method("POST"))
+                    .and(path(mock::GUEST_ACTIVATE_PATH))
+                    .respond_with(mock::json(200, &format!(r#"{{"guest_token":"{}"}}"#, token)))
+                    .expect(1),
+            )
+            .await;
+        proxy
+            .mount(
+                Mock::given(method("GET"))
+                    .and(path(mock::USER_BY_SCREEN_NAME_PATH))
+                    .and(header("x-guest-token", token))
+                    .respond_with(mock::json(200, mock::USER_BY_SCREEN_NAME))
+                    .expect(2),
+            )
+            .await;
+        proxies.push(proxy);
+    }
+
+    let scraper = TwitterScraper::builder()
+        .endpoints(Endpoints::local("http://twitter.test"))
+        .proxies(proxies.iter().map(|proxy| proxy.server().uri()))
+        .build()
+        .unwrap();
+    assert_eq!(scraper.proxies().len(), 2);
+    for _ in 0..4 {
+        scraper.get_profile("Twitter").await.unwrap();
+    }
+}
@@ -1,11 +1,91 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    #[default]
+    Top,
+    Latest,
+    Photos,
+    Videos,
+    People,
+}
+
+impl SearchMode {
+    pub(crate) fn tweet_search_mode(&self) -> Option<&'static str> {
+        match self {
+            Self::Latest => Some("live"),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn result_filter(&self) -> Option<&'static str> {
+        match self {
+            Self::Photos => Some("image"),
+            Self::Videos => Some("video"),
+            Self::People => Some("user"),
+            _ => None,
+        }
+    }
+}
+
+/// Restricts `query` to tweets within `radius_km` of the given point.
+pub fn geocode_query(query: &str, latitude: f64, longitude: f64, radius_km: f64) -> String {
+    format!("{} geocode:{},{},{}km", query, latitude, longitude, radius_km)
+        .trim()
+        .to_owned()
+}
+
+/// Restricts `query` to tweets tagged with the place `place_id`.
+pub fn place_query(query: &str, place_id: &str) -> String {
+    format!("{} place:{}", query, place_id).trim().to_owned()
+}
+
+#[cfg(test)]
 #[tokio::test]
+#[allow(clippy::len_zero)]
 async fn test_search() {
-    use crate::TwitterScraper;
-    let scraper = TwitterScraper::new();
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
     scraper.get_guest_token().await.unwrap();
-    let (_, cursor) = scraper.search("bitcoin", None).await.unwrap();
+    let (_, cursor) = scraper.search("bitcoin", SearchMode::Top, None).await.unwrap();
     assert!(cursor.is_some());
-    let (tweets, cursor) = scraper.search("bitcoin", cursor).await.unwrap();
+    let (tweets, cursor) = scraper.search("bitcoin", SearchMode::Top, cursor).await.unwrap();
     assert!(tweets.len() > 0);
+    assert!(tweets.iter().all(|t| t.place.is_none()));
     assert!(cursor.is_some());
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_search_profiles() {
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let (profiles, cursor) = mock.scraper().search_profiles("twitter", None).await.unwrap();
+    assert_eq!(profiles.len(), 2);
+    assert_eq!(profiles[0].username, "Twitter");
+    assert!(cursor.is_some());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_search_mode_params() {
+    use crate::types::adaptive::AdaptiveParams;
+    let latest = AdaptiveParams::search_params("bitcoin", SearchMode::Latest, None);
+    let latest = serde_url_params::to_string(&latest).unwrap();
+    assert!(latest.contains("tweet_search_mode=live"));
+    assert!(!latest.contains("result_filter"));
+
+    let photos = AdaptiveParams::search_params("bitcoin", SearchMode::Photos, None);
+    let photos = serde_url_params::to_string(&photos).unwrap();
+    assert!(photos.contains("result_filter=image"));
+    assert!(!photos.contains("tweet_search_mode"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_geo_queries() {
+    assert_eq!(
+        geocode_query("flood", 40.73, -73.99, 5.0),
+        "flood geocode:40.73,-73.99,5km"
+    );
+    assert_eq!(place_query("", "01a9a39529b27f36"), "place:01a9a39529b27f36");
+}
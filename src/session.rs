@@ -0,0 +1,385 @@
+use std::{collections::HashMap, fs, io::Write, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rate_limit::RateLimit,
+    types::auth::{CSRFAuth, GuestToken},
+    Error, Result,
+};
+
+const COOKIE_DOMAINS: [&str; 2] = ["twitter.com", "x.com"];
+
+/// Version of the file written by [`crate::TwitterScraper::save_session`].
+pub const SESSION_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    /// Unix timestamp (seconds); `None` for session cookies.
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl Cookie {
+    pub fn new<T: Into<String>>(name: T, value: T) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: None,
+            path: None,
+            expires: None,
+            secure: false,
+            http_only: false,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= Utc::now().timestamp())
+    }
+
+    /// Cookies without a domain, or set for twitter.com / x.com.
+    fn is_twitter(&self) -> bool {
+        match &self.domain {
+            Some(domain) => {
+                let domain = domain.trim_start_matches('.');
+                COOKIE_DOMAINS
+                    .iter()
+                    .any(|d| domain == *d || domain.ends_with(&format!(".{}", d)))
+            }
+            None => true,
+        }
+    }
+}
+
+/// Cookie jar of a logged-in browser session, sent with every request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    cookies: Vec<Cookie>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the value of a `Cookie:` request header, e.g. `auth_token=...; ct0=...`.
+    pub fn from_cookie_header(header: &str) -> Result<Self> {
+        let header = header.trim();
+        let header = header
+            .strip_prefix("Cookie:")
+            .or_else(|| header.strip_prefix("cookie:"))
+            .unwrap_or(header);
+
+        let mut session = Self::new();
+        for pair in header.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidSession(format!("malformed cookie: {}", pair)))?;
+            session.insert(Cookie::new(name.trim(), value.trim()));
+        }
+        session.validate()
+    }
+
+    /// Parses a Netscape `cookies.txt` file as exported by curl and browser extensions.
+    pub fn from_netscape(contents: &str) -> Result<Self> {
+        let mut session = Self::new();
+        for line in contents.lines() {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 7 {
+                return Err(Error::InvalidSession(format!("malformed cookies.txt line: {}", line)));
+            }
+            let expires: i64 = fields[4]
+                .parse()
+                .map_err(|_| Error::InvalidSession(format!("invalid expiry: {}", fields[4])))?;
+
+            session.insert(Cookie {
+                name: fields[5].to_owned(),
+                value: fields[6].to_owned(),
+                domain: Some(fields[0].to_owned()),
+                path: Some(fields[2].to_owned()),
+                expires: (expires > 0).then_some(expires),
+                secure: fields[3].eq_ignore_ascii_case("TRUE"),
+                http_only,
+            });
+        }
+        session.validate()
+    }
+
+    /// Parses a browser cookie export: a JSON array of cookies (EditThisCookie,
+    /// Cookie-Editor) or an object with a `cookies` array (Playwright storage state).
+    pub fn from_json(contents: &str) -> Result<Self> {
+        let cookies = match serde_json::from_str(contents)? {
+            BrowserExport::Cookies(cookies) => cookies,
+            BrowserExport::StorageState { cookies } => cookies,
+        };
+
+        let mut session = Self::new();
+        for cookie in cookies {
+            session.insert(cookie.into());
+        }
+        session.validate()
+    }
+
+    pub fn from_netscape_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_netscape(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Adds `cookie`, replacing any cookie with the same name. Cookies of other
+    /// sites are ignored, so a multi-site export cannot shadow Twitter's.
+    pub fn insert(&mut self, cookie: Cookie) {
+        if !cookie.is_twitter() {
+            return;
+        }
+        self.cookies.retain(|c| c.name != cookie.name);
+        self.cookies.push(cookie);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|c| c.name == name && c.is_twitter() && !c.is_expired())
+            .map(|c| c.value.as_str())
+    }
+
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    /// The `ct0` cookie, echoed in the `x-csrf-token` header.
+    pub fn csrf_token(&self) -> Option<&str> {
+        self.get("ct0")
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.get("auth_token").is_some()
+    }
+
+    /// Applies a `Set-Cookie` response header. Cookies set to expire are removed.
+    pub(crate) fn apply_set_cookie(&mut self, header: &str) {
+        let mut attributes = header.split(';').map(str::trim);
+        let (name, value) = match attributes.next().and_then(|pair| pair.split_once('=')) {
+            Some((name, value)) if !name.trim().is_empty() => (name.trim(), value.trim()),
+            _ => return,
+        };
+
+        let mut cookie = Cookie::new(name, value);
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            match key.to_ascii_lowercase().as_str() {
+                "domain" => cookie.domain = Some(value.to_owned()),
+                "path" => cookie.path = Some(value.to_owned()),
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    cookie.expires = DateTime::parse_from_rfc2822(value)
+                        .ok()
+                        .map(|expires| expires.timestamp())
+                }
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(Utc::now().timestamp() + max_age);
+        }
+
+        match cookie.is_expired() {
+            true => self.cookies.retain(|c| c.name != cookie.name),
+            false => self.insert(cookie),
+        }
+    }
+
+    /// `Cookie` header value with every unexpired Twitter cookie.
+    pub(crate) fn cookie_header(&self) -> Option<String> {
+        let header = self
+            .cookies
+            .iter()
+            .filter(|c| c.is_twitter() && !c.is_expired())
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        (!header.is_empty()).then_some(header)
+    }
+
+    fn validate(self) -> Result<Self> {
+        match self.cookies.is_empty() {
+            false => Ok(self),
+            true => Err(Error::InvalidSession("no twitter.com cookies".to_owned())),
+        }
+    }
+}
+
+impl From<CSRFAuth> for Session {
+    fn from(auth: CSRFAuth) -> Self {
+        let mut session = Self::new();
+        session.insert(Cookie::new("auth_token".to_owned(), auth.auth_token));
+        session.insert(Cookie::new("ct0".to_owned(), auth.csrf_token));
+        session
+    }
+}
+
+/// Scraper state persisted between runs.
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct SessionFile {
+    pub version: u32,
+    pub guest_token: Option<GuestToken>,
+    pub session: Option<Session>,
+    pub csrf_token: Option<String>,
+    #[serde(default)]
+    pub rate_limits: HashMap<String, RateLimit>,
+}
+
+impl SessionFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let file: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if file.version != SESSION_FILE_VERSION {
+            return Err(Error::InvalidSession(format!(
+                "unsupported session file version {}",
+                file.version
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Writes the file readable by the owner only, replacing `path` atomically.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_vec_pretty(self)?;
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".{}.{:016x}.tmp", std::process::id(), fastrand::u64(..)));
+
+        let mut file = create_private(Path::new(&temp))?;
+        let written = (|| {
+            file.write_all(&contents)?;
+            file.sync_all()?;
+            fs::rename(&temp, path)
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        Ok(written?)
+    }
+}
+
+/// Creates a new file readable by the owner only. It must not exist yet, so a
+/// file with looser permissions or a symlink planted at `path` is never reused.
+pub(crate) fn create_private(path: &Path) -> std::io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let file = options.open(path)?;
+    // The mode above is still subject to the umask.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(error) = file.set_permissions(fs::Permissions::from_mode(0o600)) {
+            let _ = fs::remove_file(path);
+            return Err(error);
+        }
+    }
+    Ok(file)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BrowserExport {
+    Cookies(Vec<BrowserCookie>),
+    StorageState { cookies: Vec<BrowserCookie> },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserCookie {
+    name: String,
+    value: String,
+    domain: Option<String>,
+    path: Option<String>,
+    #[serde(alias = "expires")]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+}
+
+impl From<BrowserCookie> for Cookie {
+    fn from(cookie: BrowserCookie) -> Self {
+        Self {
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            expires: cookie
+                .expiration_date
+                .filter(|expires| *expires > 0.0)
+                .map(|expires| expires as i64),
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_session_import() {
+    let header = Session::from_cookie_header("Cookie: auth_token=abc; ct0=def; lang=en").unwrap();
+    assert!(header.is_authenticated());
+    assert_eq!(header.csrf_token(), Some("def"));
+    assert_eq!(header.cookie_header().as_deref(), Some("auth_token=abc; ct0=def; lang=en"));
+
+    let netscape = Session::from_netscape(
+        "# Netscape HTTP Cookie File\n\
+         #HttpOnly_.twitter.com\tTRUE\t/\tTRUE\t0\tauth_token\tabc\n\
+         .twitter.com\tTRUE\t/\tTRUE\t0\tct0\tdef\n\
+         .example.com\tTRUE\t/\tFALSE\t0\tother\tx\n\
+         .example.com\tTRUE\t/\tFALSE\t0\tct0\tforeign\n\
+         .twitter.com\tTRUE\t/\tFALSE\t1\texpired\tx\n",
+    )
+    .unwrap();
+    assert!(netscape.cookies()[0].http_only);
+    assert_eq!(netscape.csrf_token(), Some("def"));
+    assert!(netscape.cookies().iter().all(Cookie::is_twitter));
+    assert_eq!(netscape.cookie_header().as_deref(), Some("auth_token=abc; ct0=def"));
+
+    let json = Session::from_json(
+        r#"[
+            {"name": "auth_token", "value": "abc", "domain": ".x.com", "path": "/", "expirationDate": 4102444800.5, "httpOnly": true, "secure": true},
+            {"name": "ct0", "value": "def", "domain": ".x.com", "path": "/", "session": true}
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(json.cookies()[0].expires, Some(4102444800));
+    assert_eq!(json.csrf_token(), Some("def"));
+
+    let storage_state = Session::from_json(r#"{"cookies": [{"name": "ct0", "value": "def", "domain": ".twitter.com", "expires": -1}]}"#).unwrap();
+    assert_eq!(storage_state.cookies()[0].expires, None);
+
+    assert!(matches!(Session::from_cookie_header("garbage"), Err(Error::InvalidSession(_))));
+}
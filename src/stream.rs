@@ -0,0 +1,129 @@
+use std::{collections::HashSet, future::Future};
+
+use futures::{stream, Stream, StreamExt};
+
+use crate::Result;
+
+struct PageState<F> {
+    fetch: F,
+    cursor: Option<String>,
+    seen: HashSet<String>,
+    finished: bool,
+}
+
+/// Follows the bottom cursor returned by `fetch` until a page comes back empty,
+/// the cursor is missing or repeats, or `limit` items have been yielded.
+/// An error is yielded once and ends the stream.
+pub(crate) fn paginate<'a, T, F, Fut>(
+    fetch: F,
+    limit: Option<usize>,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>> + 'a,
+{
+    let state = PageState {
+        fetch,
+        cursor: None,
+        seen: HashSet::new(),
+        finished: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        if state.finished {
+            return None;
+        }
+
+        match (state.fetch)(state.cursor.take()).await {
+            Ok((items, _)) if items.is_empty() => None,
+            Ok((items, cursor)) => {
+                match cursor {
+                    Some(cursor) if state.seen.insert(cursor.clone()) => {
+                        state.cursor = Some(cursor)
+                    }
+                    _ => state.finished = true,
+                }
+                Some((Ok(items), state))
+            }
+            Err(error) => {
+                state.finished = true;
+                Some((Err(error), state))
+            }
+        }
+    })
+    .flat_map(|page| {
+        let items: Vec<Result<T>> = match page {
+            Ok(items) => items.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        };
+        stream::iter(items)
+    })
+    .take(limit.unwrap_or(usize::MAX))
+}
+
+/// [`paginate`] over a user's pages, resolving `user_id` once before the first
+/// page rather than looking the user up again for every page.
+pub(crate) fn paginate_user<'a, T, U, F, Fut>(
+    user_id: U,
+    mut fetch: F,
+    limit: Option<usize>,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    U: Future<Output = Result<String>> + 'a,
+    F: FnMut(String, Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>> + 'a,
+{
+    stream::once(async move {
+        match user_id.await {
+            Ok(user_id) => {
+                paginate(move |cursor| fetch(user_id.clone(), cursor), limit).left_stream()
+            }
+            Err(error) => stream::iter(vec![Err(error)]).right_stream(),
+        }
+    })
+    .flatten()
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_paginate_stops_on_repeated_cursor() {
+    let pages = paginate(
+        |cursor: Option<String>| async move {
+            let next = match cursor.as_deref() {
+                None => "a",
+                Some("a") => "b",
+                _ => "a",
+            };
+            Ok((vec![next.to_owned()], Some(next.to_owned())))
+        },
+        None,
+    )
+    .collect::<Vec<_>>()
+    .await;
+
+    let items: Vec<String> = pages.into_iter().map(|x| x.unwrap()).collect();
+    assert_eq!(items, vec!["a", "b", "a"]);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_paginate_respects_limit() {
+    let mut requests = 0;
+    let items = paginate(
+        |cursor: Option<String>| {
+            requests += 1;
+            async move {
+                let page = cursor.map(|c| c.len()).unwrap_or(0);
+                Ok((vec![page; 3], Some("x".repeat(page + 1))))
+            }
+        },
+        Some(4),
+    )
+    .collect::<Vec<_>>()
+    .await;
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(requests, 2);
+}
@@ -0,0 +1,48 @@
+use std::{sync::RwLock, time::Duration};
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::types::auth::GuestToken;
+
+/// Guest tokens are accepted for roughly three hours after activation.
+pub(crate) const DEFAULT_GUEST_TOKEN_LIFETIME: Duration = Duration::from_secs(3 * 60 * 60);
+
+pub(crate) struct GuestTokenCache {
+    token: RwLock<Option<GuestToken>>,
+    refresh: Mutex<()>,
+    lifetime: Duration,
+}
+
+impl GuestTokenCache {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            token: RwLock::new(None),
+            refresh: Mutex::new(()),
+            lifetime,
+        }
+    }
+
+    pub fn get(&self) -> Option<GuestToken> {
+        self.token.read().unwrap().clone()
+    }
+
+    /// The cached token, unless it has outlived the configured lifetime.
+    pub fn valid(&self) -> Option<GuestToken> {
+        self.get().filter(|token| !token.is_expired(self.lifetime))
+    }
+
+    pub fn set(&self, token: GuestToken) {
+        *self.token.write().unwrap() = Some(token);
+    }
+
+    /// Serializes refreshes so concurrent requests activate a single new token.
+    pub async fn lock_refresh(&self) -> MutexGuard<'_, ()> {
+        self.refresh.lock().await
+    }
+}
+
+impl Default for GuestTokenCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_GUEST_TOKEN_LIFETIME)
+    }
+}
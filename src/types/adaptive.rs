@@ -1,5 +1,7 @@
 use serde::Serialize;
 
+use crate::search::SearchMode;
+
 #[derive(Serialize)]
 pub struct AdaptiveParams {
     pub q: Option<String>,
@@ -10,10 +12,12 @@ pub struct AdaptiveParams {
     pub cursor: Option<String>,
     #[serde(rename = "userId")]
     pub user_id: Option<String>,
+    pub tweet_search_mode: Option<String>,
+    pub result_filter: Option<String>,
 }
 
 impl AdaptiveParams {
-    pub fn search_params<T: Into<String>>(q: T, cursor: Option<String>) -> Self {
+    pub fn search_params<T: Into<String>>(q: T, mode: SearchMode, cursor: Option<String>) -> Self {
         Self {
             q: Some(q.into()),
             count: Some(100),
@@ -22,6 +26,8 @@ impl AdaptiveParams {
             spelling_corrections: Some(1),
             cursor,
             user_id: None,
+            tweet_search_mode: mode.tweet_search_mode().map(str::to_owned),
+            result_filter: mode.result_filter().map(str::to_owned),
         }
     }
     pub fn add_cursor(mut self, cursor: String) -> Self {
@@ -37,6 +43,8 @@ impl AdaptiveParams {
             spelling_corrections: None,
             cursor: None,
             user_id: None,
+            tweet_search_mode: None,
+            result_filter: None,
         }
     }
     pub fn user_tweets_params<T: Into<String>>(user_id: T, cursor: Option<String>) -> Self {
@@ -48,6 +56,8 @@ impl AdaptiveParams {
             spelling_corrections: None,
             cursor,
             user_id: Some(user_id.into()),
+            tweet_search_mode: None,
+            result_filter: None,
         }
     }
 }
@@ -57,3 +67,26 @@ impl Default for AdaptiveParams {
         Self::new()
     }
 }
+
+#[derive(Serialize)]
+pub struct GuideParams {
+    pub count: i32,
+    pub candidate_source: String,
+    pub include_page_configuration: bool,
+    pub entity_tokens: bool,
+    pub initial_tab_id: String,
+    pub woeid: Option<i64>,
+}
+
+impl GuideParams {
+    pub fn trends_params(woeid: Option<i64>) -> Self {
+        Self {
+            count: 20,
+            candidate_source: "trends".to_owned(),
+            include_page_configuration: false,
+            entity_tokens: false,
+            initial_tab_id: "trending".to_owned(),
+            woeid,
+        }
+    }
+}
@@ -1,11 +1,40 @@
-use serde::Deserialize;
+use std::time::Duration;
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct GuestToken{
-    pub guest_token: String
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuestToken {
+    pub guest_token: String,
+    #[serde(default = "now")]
+    pub issued_at: i64,
+}
+
+impl GuestToken {
+    pub fn is_expired(&self, lifetime: Duration) -> bool {
+        now() - self.issued_at >= lifetime.as_secs() as i64
+    }
+}
+
+fn now() -> i64 {
+    Utc::now().timestamp()
 }
 
-pub struct CSRFAuth{
+#[derive(Clone)]
+pub struct CSRFAuth {
     pub auth_token: String,
-    pub csrf_token: String
-}
\ No newline at end of file
+    pub csrf_token: String,
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_guest_token_expiry() {
+    let token: GuestToken = serde_json::from_str(r#"{"guest_token": "1"}"#).unwrap();
+    assert!(!token.is_expired(Duration::from_secs(60)));
+
+    let token = GuestToken {
+        issued_at: now() - 120,
+        ..token
+    };
+    assert!(token.is_expired(Duration::from_secs(60)));
+}
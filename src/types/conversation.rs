@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+use super::{timeline::TweetRaw, tweet::Tweet};
+
+#[derive(Deserialize, Debug)]
+pub struct TweetDetailResponse {
+    data: TweetDetailData,
+}
+
+#[derive(Deserialize, Debug)]
+struct TweetDetailData {
+    threaded_conversation_with_injections_v2: Option<ConversationTimeline>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ConversationTimeline {
+    instructions: Vec<Instruction>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Instruction {
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Entry {
+    content: EntryContent,
+}
+
+#[derive(Deserialize, Debug)]
+struct EntryContent {
+    #[serde(rename = "itemContent")]
+    item_content: Option<ItemContent>,
+    #[serde(default)]
+    items: Vec<ModuleItem>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ModuleItem {
+    item: ModuleItemContent,
+}
+
+#[derive(Deserialize, Debug)]
+struct ModuleItemContent {
+    #[serde(rename = "itemContent")]
+    item_content: Option<ItemContent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ItemContent {
+    tweet_results: Option<TweetResults>,
+    value: Option<String>,
+    #[serde(rename = "cursorType")]
+    cursor_type: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TweetResults {
+    result: Option<TweetResult>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TweetResult {
+    core: Option<TweetCore>,
+    quoted_status_result: Option<Box<TweetResults>>,
+    legacy: Option<TweetRaw>,
+    source: Option<String>,
+    tweet: Option<Box<TweetResult>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TweetCore {
+    user_results: UserResults,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserResults {
+    result: UserResult,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserResult {
+    legacy: UserLegacy,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserLegacy {
+    screen_name: String,
+}
+
+impl TweetResults {
+    pub(crate) fn to_tweet(&self) -> Option<Tweet> {
+        self.result.as_ref()?.to_tweet()
+    }
+}
+
+impl TweetResult {
+    pub(crate) fn to_tweet(&self) -> Option<Tweet> {
+        if let Some(tweet) = &self.tweet {
+            return tweet.to_tweet();
+        }
+
+        let username = &self.core.as_ref()?.user_results.result.legacy.screen_name;
+        let legacy = self.legacy.as_ref()?;
+        let mut tweet = legacy.to_tweet(username)?;
+        if let Some(source) = &self.source {
+            tweet.source = source.to_owned();
+        }
+        tweet.quoted_status = self
+            .quoted_status_result
+            .as_ref()
+            .and_then(|t| t.to_tweet())
+            .map(Box::new);
+        tweet.retweeted_status = legacy
+            .retweeted_status_result
+            .as_ref()
+            .and_then(|t| t.to_tweet())
+            .map(Box::new);
+        tweet.is_quoted |= tweet.quoted_status.is_some();
+        Some(tweet)
+    }
+}
+
+impl TweetDetailResponse {
+    pub fn parse_tweets(self) -> (Vec<Tweet>, Option<String>) {
+        let mut cursor = None;
+        let mut tweets = vec![];
+
+        let instructions = match self.data.threaded_conversation_with_injections_v2 {
+            Some(timeline) => timeline.instructions,
+            None => return (tweets, cursor),
+        };
+
+        for entry in instructions.iter().flat_map(|i| i.entries.iter()) {
+            let module_items = entry
+                .content
+                .items
+                .iter()
+                .filter_map(|i| i.item.item_content.as_ref());
+
+            for item in entry.content.item_content.iter().chain(module_items) {
+                if let Some(tweet) = item.tweet_results.as_ref().and_then(|t| t.to_tweet()) {
+                    tweets.push(tweet);
+                }
+                if item.cursor_type.as_deref() == Some("Bottom") {
+                    cursor = item.value.to_owned();
+                }
+            }
+        }
+
+        (tweets, cursor)
+    }
+}
+
+#[derive(Debug)]
+pub struct Conversation {
+    pub focal: Tweet,
+    /// Tweets the focal tweet replies to, oldest first, with their other replies.
+    /// The next ancestor and the focal tweet are not repeated among them.
+    pub ancestors: Vec<ConversationNode>,
+    pub replies: Vec<ConversationNode>,
+}
+
+#[derive(Debug)]
+pub struct ConversationNode {
+    pub tweet: Tweet,
+    pub replies: Vec<ConversationNode>,
+}
+
+impl Conversation {
+    /// Links `tweets` into a tree around `focal_id`. Replies whose parent is not
+    /// part of the conversation are attached directly below the focal tweet.
+    pub fn build(focal_id: &str, tweets: Vec<Tweet>) -> Option<Self> {
+        let mut tweets = tweets;
+        let position = tweets.iter().position(|t| t.id.to_string() == focal_id)?;
+        let focal = tweets.remove(position);
+
+        let mut ancestors = vec![];
+        let mut parent = focal.in_reply_to_status.clone();
+        while let Some(parent_id) = parent {
+            match tweets.iter().position(|t| t.id.to_string() == parent_id) {
+                Some(position) => {
+                    let ancestor = tweets.remove(position);
+                    parent = ancestor.in_reply_to_status.clone();
+                    ancestors.push(ancestor);
+                }
+                None => parent = None,
+            }
+        }
+        ancestors.reverse();
+
+        let mut conversation = Self {
+            focal,
+            ancestors: ancestors
+                .into_iter()
+                .map(|tweet| ConversationNode {
+                    tweet,
+                    replies: vec![],
+                })
+                .collect(),
+            replies: vec![],
+        };
+        conversation.extend(tweets);
+        Some(conversation)
+    }
+
+    /// Adds the replies of a later page, linked to the tweets they reply to.
+    pub fn extend(&mut self, tweets: Vec<Tweet>) {
+        let mut seen = HashSet::new();
+        seen.insert(self.focal.id);
+        for node in self.ancestors.iter().chain(self.replies.iter()) {
+            node.collect_ids(&mut seen);
+        }
+        let tweets: Vec<Tweet> = tweets
+            .into_iter()
+            .filter(|t| seen.insert(t.id))
+            .collect();
+
+        let ids: HashSet<String> = seen.iter().map(|id| id.to_string()).collect();
+        let focal_id = self.focal.id.to_string();
+        let mut children: HashMap<String, Vec<Tweet>> = HashMap::new();
+        for tweet in tweets {
+            let parent_id = match &tweet.in_reply_to_status {
+                Some(parent_id) if ids.contains(parent_id) => parent_id.to_owned(),
+                _ => focal_id.to_owned(),
+            };
+            children.entry(parent_id).or_default().push(tweet);
+        }
+
+        self.replies
+            .extend(ConversationNode::build(&focal_id, &mut children));
+        for node in self.ancestors.iter_mut().chain(self.replies.iter_mut()) {
+            node.attach(&mut children);
+        }
+    }
+}
+
+impl ConversationNode {
+    fn collect_ids(&self, ids: &mut HashSet<i64>) {
+        ids.insert(self.tweet.id);
+        for reply in &self.replies {
+            reply.collect_ids(ids);
+        }
+    }
+
+    /// Moves the replies to this node or its descendants out of `children`.
+    fn attach(&mut self, children: &mut HashMap<String, Vec<Tweet>>) {
+        self.replies
+            .extend(Self::build(&self.tweet.id.to_string(), children));
+        for reply in self.replies.iter_mut() {
+            reply.attach(children);
+        }
+    }
+
+    fn build(parent_id: &str, children: &mut HashMap<String, Vec<Tweet>>) -> Vec<Self> {
+        children
+            .remove(parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tweet| {
+                let replies = Self::build(&tweet.id.to_string(), children);
+                Self { tweet, replies }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_build_conversation() {
+    let tweet = |id: &str, user: &str, parent: Option<&str>| -> String {
+        let parent = parent
+            .map(|p| format!(r#""in_reply_to_status_id_str": "{}", "in_reply_to_screen_name": "x","#, p))
+            .unwrap_or_default();
+        format!(
+            r#"{{"itemContent": {{"tweet_results": {{"result": {{
+                "__typename": "Tweet",
+                "core": {{"user_results": {{"result": {{"legacy": {{"screen_name": "{user}"}}}}}}}},
+                "legacy": {{
+                    "created_at": "Wed Oct 10 20:19:24 +0000 2018",
+                    "id_str": "{id}", "full_text": "tweet {id}", {parent}
+                    "entities": {{}}, "user_id_str": "1", "is_quote_status": false,
+                    "retweet_count": 0, "favorite_count": 0, "retweeted": false
+                }}
+            }}}}}}}}"#
+        )
+    };
+
+    let body = format!(
+        r#"{{"data": {{"threaded_conversation_with_injections_v2": {{"instructions": [{{
+            "type": "TimelineAddEntries",
+            "entries": [
+                {{"content": {}}},
+                {{"content": {}}},
+                {{"content": {{"items": [{{"item": {}}}, {{"item": {}}}]}}}},
+                {{"content": {{"items": [{{"item": {}}}]}}}},
+                {{"content": {{"itemContent": {{"value": "next", "cursorType": "Bottom"}}}}}}
+            ]
+        }}]}}}}}}"#,
+        tweet("1", "root", None),
+        tweet("2", "focal", Some("1")),
+        tweet("3", "a", Some("2")),
+        tweet("4", "focal", Some("3")),
+        tweet("5", "b", Some("2")),
+    );
+
+    let response: TweetDetailResponse = serde_json::from_str(&body).unwrap();
+    let (tweets, cursor) = response.parse_tweets();
+    assert_eq!(cursor.as_deref(), Some("next"));
+
+    let mut conversation = Conversation::build("2", tweets).unwrap();
+    assert_eq!(conversation.focal.username, "focal");
+    assert_eq!(conversation.ancestors.len(), 1);
+    assert_eq!(conversation.ancestors[0].tweet.id, 1);
+    assert!(conversation.ancestors[0].replies.is_empty());
+    assert_eq!(conversation.replies.len(), 2);
+    assert_eq!(conversation.replies[0].tweet.id, 3);
+    assert_eq!(conversation.replies[0].replies[0].tweet.id, 4);
+    assert_eq!(conversation.replies[1].tweet.id, 5);
+
+    let page = format!(
+        r#"{{"data": {{"threaded_conversation_with_injections_v2": {{"instructions": [{{
+            "entries": [
+                {{"content": {}}},
+                {{"content": {{"items": [{{"item": {}}}, {{"item": {}}}, {{"item": {}}}]}}}}
+            ]
+        }}]}}}}}}"#,
+        tweet("3", "a", Some("2")),
+        tweet("6", "c", Some("1")),
+        tweet("7", "d", Some("6")),
+        tweet("8", "e", Some("4")),
+    );
+    let response: TweetDetailResponse = serde_json::from_str(&page).unwrap();
+    let (tweets, cursor) = response.parse_tweets();
+    assert!(cursor.is_none());
+
+    conversation.extend(tweets);
+    assert_eq!(conversation.replies.len(), 2);
+    assert_eq!(conversation.replies[0].replies[0].replies[0].tweet.id, 8);
+    let sibling = &conversation.ancestors[0].replies;
+    assert_eq!(sibling.len(), 1);
+    assert_eq!(sibling[0].tweet.id, 6);
+    assert_eq!(sibling[0].replies[0].tweet.id, 7);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_conversation_pages() {
+    use crate::mock::{self, MockTwitter};
+    use wiremock::{matchers::path, Mock, Request};
+
+    let mock = MockTwitter::start().await;
+    mock.mount(
+        Mock::given(path(mock::TWEET_DETAIL_PATH))
+            .and(|request: &Request| request.url.query().unwrap_or("").contains("cursor"))
+            .respond_with(mock::json(
+                200,
+                r#"{"data": {"threaded_conversation_with_injections_v2": {"instructions": []}}}"#,
+            ))
+            .with_priority(1)
+            .expect(1),
+    )
+    .await;
+
+    let scraper = mock.scraper();
+    let (mut conversation, cursor) = scraper.get_conversation("2").await.unwrap();
+    assert_eq!(conversation.ancestors[0].tweet.id, 1);
+    assert_eq!(conversation.replies[0].tweet.id, 3);
+
+    let cursor = scraper
+        .get_more_replies(&mut conversation, cursor.unwrap())
+        .await
+        .unwrap();
+    assert!(cursor.is_none());
+    assert_eq!(conversation.replies.len(), 1);
+    assert_eq!(mock.server().received_requests().await.unwrap().len(), 3);
+}
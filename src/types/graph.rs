@@ -39,6 +39,7 @@ struct GraphResponseTimeline {
 #[serde(untagged)]
 enum GraphResponseInstructions {
     Valid(ValidInstruction),
+    #[allow(dead_code)]
     Ignore(IgnoreInstruction),
 }
 
@@ -129,7 +130,50 @@ impl From<GraphResponseResult> for Error {
     fn from(value: GraphResponseResult) -> Self {
         match value.__typename.as_str() {
             "UserUnavailable" => Self::UnauthorizedToViewSpecificUser,
-            _ => Self::UnknownError,
+            _ => Self::unknown(),
         }
     }
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_followers_and_following() {
+    use crate::mock::MockTwitter;
+    use futures::StreamExt;
+
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
+
+    let (followers, cursor) = scraper.get_followers("Twitter", None).await.unwrap();
+    assert_eq!(followers.len(), 2);
+    assert_eq!(followers[0].username, "jack");
+    assert!(cursor.is_some());
+
+    let (following, _) = scraper.get_following("Twitter", None).await.unwrap();
+    assert_eq!(following[0].username, "elonmusk");
+
+    let streamed: Vec<_> = scraper.followers_stream("Twitter", Some(3)).collect().await;
+    assert_eq!(streamed.len(), 3);
+    assert!(streamed.iter().all(|p| p.is_ok()));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_followers_stream_looks_up_profile_once() {
+    use crate::mock::{MockTwitter, USER_BY_SCREEN_NAME_PATH};
+    use futures::StreamExt;
+
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
+
+    // Two pages of two followers each.
+    let streamed: Vec<_> = scraper.followers_stream("Twitter", Some(4)).collect().await;
+    assert_eq!(streamed.len(), 4);
+
+    let requests = mock.server().received_requests().await.unwrap();
+    let lookups = requests
+        .iter()
+        .filter(|request| request.url.path() == USER_BY_SCREEN_NAME_PATH)
+        .count();
+    assert_eq!(lookups, 1);
+}
@@ -2,8 +2,11 @@ pub mod auth;
 pub mod profile;
 pub mod tweet;
 pub mod video;
+pub mod photo;
 pub mod place;
 pub mod timeline;
 pub mod adaptive;
 pub mod params;
-pub mod graph;
\ No newline at end of file
+pub mod graph;
+pub mod trend;
+pub mod conversation;
\ No newline at end of file
@@ -18,6 +18,7 @@ struct ParamsUrl {
 struct Variables {
     user_id: String,
     count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     cursor: Option<String>,
     include_promoted_content: bool,
     with_super_follows_user_fields: bool,
@@ -27,6 +28,27 @@ struct Variables {
     with_super_follows_tweet_fields: bool,
 }
 
+pub struct TweetDetailParams {
+    variables: TweetDetailVariables,
+    features: Features,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TweetDetailVariables {
+    focal_tweet_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+    #[serde(rename = "with_rux_injections")]
+    with_rux_injections: bool,
+    include_promoted_content: bool,
+    with_community: bool,
+    with_quick_promote_eligibility_tweet_fields: bool,
+    with_birdwatch_notes: bool,
+    with_voice: bool,
+    with_v2_timeline: bool,
+}
+
 #[derive(Serialize)]
 struct Features {
     responsive_web_twitter_blue_verified_badge_is_enabled: bool,
@@ -62,6 +84,22 @@ impl Variables {
     }
 }
 
+impl TweetDetailVariables {
+    pub fn new<T: Into<String>>(tweet_id: T, cursor: Option<T>) -> Self {
+        Self {
+            focal_tweet_id: tweet_id.into(),
+            cursor: cursor.map(|c| c.into()),
+            with_rux_injections: false,
+            include_promoted_content: false,
+            with_community: true,
+            with_quick_promote_eligibility_tweet_fields: false,
+            with_birdwatch_notes: false,
+            with_voice: true,
+            with_v2_timeline: true,
+        }
+    }
+}
+
 impl Features {
     pub fn new() -> Self {
         Self {
@@ -104,6 +142,33 @@ impl TryFrom<Params> for ParamsUrl {
     }
 }
 
+impl TryFrom<TweetDetailParams> for ParamsUrl {
+    type Error = Error;
+
+    fn try_from(value: TweetDetailParams) -> Result<Self> {
+        let features = serde_json::to_string(&value.features)?;
+        let variables = serde_json::to_string(&value.variables)?;
+
+        Ok(Self {
+            features,
+            variables,
+        })
+    }
+}
+
+impl TweetDetailParams {
+    pub fn new<T: Into<String>>(tweet_id: T, cursor: Option<T>) -> Self {
+        Self {
+            variables: TweetDetailVariables::new(tweet_id, cursor),
+            features: Features::default(),
+        }
+    }
+    pub fn to_url(self) -> Result<String> {
+        let url_params: ParamsUrl = self.try_into()?;
+        serde_url_params::to_string(&url_params).map_err(Error::from)
+    }
+}
+
 impl Params {
     pub fn new<T: Into<String>>(user_id: T, cursor: Option<T>) -> Self {
         Self {
@@ -121,6 +186,7 @@ impl Params {
     }
 }
 
+#[cfg(test)]
 #[tokio::test]
 async fn test_valid_serialization() {
     let test = Params::new("627673190", None).to_url().unwrap();
@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct Photo {
+    pub id: String,
+    pub url: String,
+    pub alt_text: Option<String>,
+}
@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Place {
     pub id: String,
     pub place_type: String,
@@ -6,4 +8,19 @@ pub struct Place {
     pub full_name: String,
     pub country_code: String,
     pub country: String,
+    pub bounding_box: Option<BoundingBox>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoundingBox {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// GeoJSON polygon rings of `[longitude, latitude]` pairs.
+    pub coordinates: Vec<Vec<[f64; 2]>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
 }
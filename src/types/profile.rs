@@ -109,24 +109,30 @@ impl TryFrom<TwitterUser> for Profile {
     }
 }
 
+#[cfg(test)]
 #[tokio::test]
+#[allow(clippy::assertions_on_constants)]
 async fn test_profile_not_found() {
-    use crate::TwitterScraper;
-    let scraper = TwitterScraper::new();
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
     scraper.get_guest_token().await.unwrap();
-    let profile = scraper.get_profile("ADLP40329lfdslfkdDJKSAHDkJ").await;
+    let profile = scraper.get_profile("not_found").await;
     let error = profile.unwrap_err();
     match error {
         Error::UserNotFound => (),
         _ => assert!(false),
     }
 }
+#[cfg(test)]
 #[tokio::test]
+#[allow(clippy::assertions_on_constants, clippy::bool_assert_comparison)]
 async fn test_profile_error_suspended() {
-    use crate::TwitterScraper;
-    let scraper = TwitterScraper::new();
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
     scraper.get_guest_token().await.unwrap();
-    let profile = scraper.get_profile("123").await;
+    let profile = scraper.get_profile("suspended").await;
     assert_eq!(profile.is_err(), true);
     let error = profile.unwrap_err();
     match error {
@@ -134,12 +140,34 @@ async fn test_profile_error_suspended() {
         _ => assert!(false),
     }
 }
+#[cfg(test)]
 #[tokio::test]
+async fn test_profile_unauthorized() {
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
+    let protected = scraper.get_profile("protected").await.unwrap_err();
+    assert!(matches!(protected, Error::UnauthorizedToViewSpecificUser));
+    let unauthorized = scraper.get_profile("unauthorized").await.unwrap_err();
+    assert!(matches!(unauthorized, Error::Unauthorized));
+}
+#[cfg(test)]
+#[tokio::test]
+async fn test_profile_rate_limited() {
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let profile = mock.scraper().get_profile("rate_limited").await;
+    assert!(matches!(profile.unwrap_err(), Error::RateLimitExceeded));
+}
+#[cfg(test)]
+#[tokio::test]
+#[allow(clippy::bool_assert_comparison)]
 async fn test_profile_valid() {
-    use crate::TwitterScraper;
-    let scraper = TwitterScraper::new();
+    use crate::mock::MockTwitter;
+    let mock = MockTwitter::start().await;
+    let scraper = mock.scraper();
     scraper.get_guest_token().await.unwrap();
-    let profile = scraper.get_profile("elonmusk").await;
+    let profile = scraper.get_profile("Twitter").await;
     assert!(profile.is_ok());
     let profile = profile.unwrap();
     assert_eq!(profile.is_private, false);
@@ -1,78 +1,119 @@
-use super::tweet::Tweet;
+use super::{
+    conversation::TweetResults,
+    photo::Photo,
+    place::{Coordinates, Place},
+    profile::Profile,
+    trend::{Trend, TrendRaw},
+    tweet::Tweet,
+    video::{Video, VideoVariant},
+};
+use crate::{Error, Result};
 use chrono::NaiveDateTime;
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
 use std::collections::HashMap;
 
+/// Twitter nests at most a retweet of a quote.
+const MAX_NESTED_TWEETS: usize = 2;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TwitterTimelineResponse {
+    #[serde(default)]
     global_objects: GlobalObjects,
     timeline: Timeline,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct GlobalObjects {
+    #[serde(default)]
     tweets: HashMap<String, TweetRaw>,
+    #[serde(default)]
     users: HashMap<String, User>,
 }
 
 #[derive(Debug, Deserialize)]
 struct User {
-    // pub id: i64,
-    // pub id_str: String,
-    // pub name: String,
+    pub id_str: String,
+    pub name: String,
     pub screen_name: String,
-    // pub description: String,
-    // pub url: Option<String>,
-    // pub entities: Entities,
-    // pub protected: bool,
-    // pub followers_count: i64,
-    // pub friends_count: i64,
-    // pub listed_count: i64,
-    // pub created_at: String,
-    // pub favourites_count: i64,
-    // pub verified: bool,
-    // pub media_count: i64,
-    // pub lang: Option<String>,
-    // pub contributors_enabled: bool,
-    // pub is_translator: bool,
-    // pub is_translation_enabled: bool,
-    // pub has_extended_profile: bool,
-    // pub default_profile: bool,
-    // pub default_profile_image: bool,
-    // pub has_custom_timelines: bool,
-    // pub business_profile_state: String,
-    // pub translator_type: String,
-    // pub require_some_consent: bool,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub entities: UserEntities,
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub protected: bool,
+    #[serde(default)]
+    pub followers_count: i64,
+    #[serde(default)]
+    pub friends_count: i64,
+    #[serde(default)]
+    pub listed_count: i64,
+    pub created_at: String,
+    #[serde(default)]
+    pub favourites_count: i64,
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub statuses_count: i64,
+    #[serde(default)]
+    pub profile_image_url_https: String,
+    pub profile_banner_url: Option<String>,
+    #[serde(default)]
+    pub pinned_tweet_ids_str: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct UserEntities {
+    pub url: Option<UserEntitiesUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserEntitiesUrl {
+    #[serde(default)]
+    pub urls: Vec<ExpandedUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpandedUrl {
+    pub expanded_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct TweetRaw {
+pub(crate) struct TweetRaw {
     pub created_at: String,
-    pub id: i64,
     pub id_str: String,
+    #[serde(alias = "full_text")]
     pub text: String,
     // pub truncated: bool,
-    pub entities: Entities,
+    entities: Entities,
+    extended_entities: Option<ExtendedEntities>,
+    #[serde(default)]
     pub source: String,
     // pub in_reply_to_status_id: Option<i64>,
     pub in_reply_to_status_id_str: Option<String>,
     // pub in_reply_to_user_id: Option<i64>,
     // pub in_reply_to_user_id_str: Option<String>,
     pub in_reply_to_screen_name: Option<String>,
-    pub user_id: i64,
     pub user_id_str: String,
     pub is_quote_status: bool,
     // pub quoted_status_id: Option<i64>,
-    // pub quoted_status_id_str: Option<String>,
+    pub quoted_status_id_str: Option<String>,
+    pub retweeted_status_id_str: Option<String>,
+    pub retweeted_status_result: Option<Box<TweetResults>>,
     pub retweet_count: i64,
     pub favorite_count: i64,
     // pub conversation_id: i64,
     // pub conversation_id_str: String,
     // pub favorited: bool,
-    pub retweeted: bool,
+    // pub retweeted: bool,
     pub possibly_sensitive: Option<bool>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub place: Option<Place>,
+    #[serde(default, deserialize_with = "lenient")]
+    coordinates: Option<Point>,
     // pub possibly_sensitive_editable: Option<bool>,
     // pub lang: Option<String>,
 }
@@ -87,6 +128,47 @@ struct Entities {
     pub user_mentions: Vec<UserMention>,
     #[serde(default)]
     pub urls: Vec<Url>,
+    #[serde(default)]
+    pub media: Vec<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Point {
+    pub coordinates: [f64; 2],
+}
+
+/// Treats a partial or malformed object as absent, so one deleted place (sent
+/// as `{}`) cannot fail the whole page.
+fn lenient<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtendedEntities {
+    #[serde(default)]
+    pub media: Vec<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Media {
+    pub id_str: String,
+    pub media_url_https: String,
+    #[serde(rename = "type")]
+    pub media_type: String,
+    pub ext_alt_text: Option<String>,
+    pub video_info: Option<VideoInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VideoInfo {
+    pub duration_millis: Option<i64>,
+    #[serde(default)]
+    pub variants: Vec<VideoVariant>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -131,6 +213,20 @@ struct Entry {
 struct Content {
     pub operation: Option<Operation>,
     pub item: Option<TweetCursorItem>,
+    pub timeline_module: Option<TimelineModule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimelineModule {
+    #[serde(default)]
+    pub items: Vec<ModuleItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModuleItem {
+    pub item: TweetCursorItem,
 }
 
 #[derive(Debug, Deserialize)]
@@ -155,7 +251,9 @@ struct TweetCursorItem {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct TweetCursorContent {
-    pub tweet: CursorTweet,
+    pub tweet: Option<CursorTweet>,
+    pub user: Option<CursorUser>,
+    pub trend: Option<TrendRaw>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -164,35 +262,74 @@ struct CursorTweet {
     pub id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CursorUser {
+    pub id: String,
+}
+
 impl TwitterTimelineResponse {
     pub fn parse_tweets(self) -> (Vec<Tweet>, Option<String>) {
-        let mut cursor = None;
-        let mut tweets = vec![];
-        for instruction in self.timeline.instructions.iter() {
-            if let Some(entries) = &instruction.add_entries {
-                for entry in entries.entries.iter() {
-                    if let Some(tweet_entry) = &entry.content.item {
-                        if let Some(tweet) = self.parse_tweet(&tweet_entry.content.tweet.id) {
-                            tweets.push(tweet)
-                        }
-                    }
-                    if let Some(operation) = &entry.content.operation {
-                        if operation
-                            .cursor
-                            .cursor_type
-                            .to_lowercase()
-                            .starts_with("bottom")
-                        {
-                            cursor = Some(operation.cursor.value.to_owned());
-                        }
-                    }
-                }
-            }
-        }
+        let tweets = self
+            .entries()
+            .filter_map(|entry| entry.content.item.as_ref()?.content.tweet.as_ref())
+            .filter_map(|tweet| self.parse_tweet(&tweet.id))
+            .collect();
 
-        (tweets, cursor)
+        (tweets, self.bottom_cursor())
+    }
+    pub fn parse_users(self) -> (Vec<Profile>, Option<String>) {
+        let profiles = self
+            .entries()
+            .filter_map(|entry| entry.content.item.as_ref()?.content.user.as_ref())
+            .filter_map(|user| self.parse_user(&user.id))
+            .collect();
+
+        (profiles, self.bottom_cursor())
+    }
+    pub fn parse_trends(self) -> Vec<Trend> {
+        self.entries()
+            .filter_map(|entry| entry.content.timeline_module.as_ref())
+            .flat_map(|module| module.items.iter())
+            .filter_map(|module_item| module_item.item.content.trend.as_ref())
+            .map(Trend::from)
+            .collect()
+    }
+    pub fn parse_user(&self, user_id: &str) -> Option<Profile> {
+        let user = self.global_objects.users.get(user_id)?;
+        Profile::try_from(user).ok()
     }
     pub fn parse_tweet(&self, tweet_id: &str) -> Option<Tweet> {
+        self.parse_nested_tweet(tweet_id, MAX_NESTED_TWEETS)
+    }
+
+    /// Every entry added by the timeline's instructions, in order.
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.timeline
+            .instructions
+            .iter()
+            .filter_map(|instruction| instruction.add_entries.as_ref())
+            .flat_map(|add_entries| add_entries.entries.iter())
+    }
+
+    /// The last `Bottom` cursor, which fetches the next page.
+    fn bottom_cursor(&self) -> Option<String> {
+        self.entries()
+            .filter_map(|entry| entry.content.operation.as_ref())
+            .filter(|operation| {
+                operation
+                    .cursor
+                    .cursor_type
+                    .to_lowercase()
+                    .starts_with("bottom")
+            })
+            .last()
+            .map(|operation| operation.cursor.value.to_owned())
+    }
+
+    /// Resolves quoted and retweeted tweets up to `depth` levels, so that a
+    /// tweet referencing itself cannot recurse forever.
+    fn parse_nested_tweet(&self, tweet_id: &str, depth: usize) -> Option<Tweet> {
         let raw_tweet_info = self.global_objects.tweets.get(tweet_id)?;
         let username = self
             .global_objects
@@ -201,63 +338,342 @@ impl TwitterTimelineResponse {
             .screen_name
             .as_str();
 
+        let mut tweet = raw_tweet_info.to_tweet(username)?;
+        let Some(depth) = depth.checked_sub(1) else {
+            return Some(tweet);
+        };
+        tweet.quoted_status = raw_tweet_info
+            .quoted_status_id_str
+            .as_deref()
+            .and_then(|id| self.parse_nested_tweet(id, depth))
+            .map(Box::new);
+        tweet.retweeted_status = raw_tweet_info
+            .retweeted_status_id_str
+            .as_deref()
+            .and_then(|id| self.parse_nested_tweet(id, depth))
+            .map(Box::new);
+
+        Some(tweet)
+    }
+}
+
+impl TweetRaw {
+    pub(crate) fn to_tweet(&self, username: &str) -> Option<Tweet> {
         let time_parsed =
-            match NaiveDateTime::parse_from_str(&raw_tweet_info.created_at, "%a %b %d %T %z %Y") {
-                Ok(t) => Some(t),
-                Err(_) => None,
-            }?;
+            NaiveDateTime::parse_from_str(&self.created_at, "%a %b %d %T %z %Y").ok()?;
 
-        let urls = raw_tweet_info
+        let urls = self
             .entities
             .urls
             .iter()
             .map(|h| h.url.to_owned())
             .collect();
 
-        let hashtags = raw_tweet_info
+        let hashtags = self
             .entities
             .hashtags
             .iter()
             .map(|h| h.text.to_owned())
             .collect();
 
-        let symbols = raw_tweet_info
+        let symbols = self
             .entities
             .symbols
             .iter()
             .map(|s| s.text.to_owned())
             .collect();
 
-        let mentions = raw_tweet_info
+        let mentions = self
             .entities
             .user_mentions
             .iter()
             .map(|m| m.screen_name.to_owned())
             .collect();
 
+        let media = match &self.extended_entities {
+            Some(extended) => &extended.media,
+            None => &self.entities.media,
+        };
+
+        let photos = media
+            .iter()
+            .filter(|m| m.media_type == "photo")
+            .map(Photo::from)
+            .collect();
+
+        let videos = media
+            .iter()
+            .filter(|m| m.media_type == "video")
+            .map(Video::from)
+            .collect();
+
+        let gifs = media
+            .iter()
+            .filter(|m| m.media_type == "animated_gif")
+            .map(Video::from)
+            .collect();
+
         Some(Tweet {
-            id: raw_tweet_info.id,
-            in_reply_to_status: raw_tweet_info.in_reply_to_status_id_str.clone(),
-            is_quoted: raw_tweet_info.is_quote_status,
-            is_reply: raw_tweet_info.in_reply_to_screen_name.is_some(),
-            is_retweet: raw_tweet_info.retweeted,
-            likes: raw_tweet_info.favorite_count,
+            id: self.id_str.parse().ok()?,
+            in_reply_to_status: self.in_reply_to_status_id_str.clone(),
+            is_quoted: self.is_quote_status || self.quoted_status_id_str.is_some(),
+            is_reply: self.in_reply_to_screen_name.is_some(),
+            is_retweet: self.retweeted_status_id_str.is_some()
+                || self.retweeted_status_result.is_some(),
+            likes: self.favorite_count,
             permanent_url: format!(
                 "https://twitter.com/{}/status/{}",
-                username, raw_tweet_info.id_str
+                username, self.id_str
             ),
-            retweets: raw_tweet_info.retweet_count,
-            text: raw_tweet_info.text.to_owned(),
-            timestamp: time_parsed.timestamp(),
-            user_id: raw_tweet_info.user_id,
+            retweets: self.retweet_count,
+            text: self.text.to_owned(),
+            timestamp: time_parsed.and_utc().timestamp(),
+            user_id: self.user_id_str.parse().ok()?,
             username: username.to_owned(),
-            sensitive_content: raw_tweet_info.possibly_sensitive.unwrap_or(false),
-            source: raw_tweet_info.source.to_owned(),
+            sensitive_content: self.possibly_sensitive.unwrap_or(false),
+            source: self.source.to_owned(),
             mentions,
             time_parsed,
             hashtags,
             symbols,
             urls,
+            photos,
+            videos,
+            gifs,
+            place: self.place.clone(),
+            coordinates: self.coordinates.as_ref().map(|point| Coordinates {
+                longitude: point.coordinates[0],
+                latitude: point.coordinates[1],
+            }),
+            quoted_status: None,
+            retweeted_status: None,
         })
     }
 }
+
+impl From<&Media> for Photo {
+    fn from(media: &Media) -> Self {
+        Self {
+            id: media.id_str.to_owned(),
+            url: media.media_url_https.to_owned(),
+            alt_text: media.ext_alt_text.to_owned(),
+        }
+    }
+}
+
+impl From<&Media> for Video {
+    fn from(media: &Media) -> Self {
+        let variants = media
+            .video_info
+            .as_ref()
+            .map(|v| v.variants.clone())
+            .unwrap_or_default();
+
+        let url = variants
+            .iter()
+            .filter(|v| v.content_type == "video/mp4")
+            .max_by_key(|v| v.bitrate.unwrap_or(0))
+            .or_else(|| variants.first())
+            .map(|v| v.url.to_owned())
+            .unwrap_or_default();
+
+        Self {
+            id: media.id_str.to_owned(),
+            preview: media.media_url_https.to_owned(),
+            url,
+            duration_ms: media.video_info.as_ref().and_then(|v| v.duration_millis),
+            variants,
+        }
+    }
+}
+
+impl TryFrom<&User> for Profile {
+    type Error = Error;
+
+    fn try_from(user: &User) -> Result<Self> {
+        let joined = NaiveDateTime::parse_from_str(&user.created_at, "%a %b %d %T %z %Y")?;
+        let website = user
+            .entities
+            .url
+            .as_ref()
+            .and_then(|u| u.urls.first())
+            .and_then(|u| u.expanded_url.to_owned());
+
+        Ok(Self {
+            avatar: user.profile_image_url_https.to_owned(),
+            banner: user.profile_banner_url.to_owned(),
+            biography: user.description.to_owned(),
+            followers_count: user.followers_count,
+            following_count: user.friends_count,
+            friends_count: user.friends_count,
+            is_private: user.protected,
+            is_verified: user.verified,
+            joined,
+            likes_count: user.favourites_count,
+            listed_count: user.listed_count,
+            location: user.location.to_owned(),
+            name: user.name.to_owned(),
+            pinned_tweet_ids: user.pinned_tweet_ids_str.to_owned(),
+            tweets_count: user.statuses_count,
+            url: format!("https://twitter.com/{}", user.screen_name),
+            user_id: user.id_str.to_owned(),
+            username: user.screen_name.to_owned(),
+            website,
+        })
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_parse_users() {
+    let response: TwitterTimelineResponse = serde_json::from_str(
+        r#"{
+            "globalObjects": {
+                "tweets": {},
+                "users": {
+                    "783214": {
+                        "id_str": "783214",
+                        "name": "Twitter",
+                        "screen_name": "Twitter",
+                        "description": "What's happening?!",
+                        "entities": {"url": {"urls": [{"expanded_url": "https://about.twitter.com"}]}},
+                        "location": "everywhere",
+                        "followers_count": 100,
+                        "friends_count": 10,
+                        "favourites_count": 7,
+                        "created_at": "Tue Feb 20 14:35:54 +0000 2007",
+                        "profile_image_url_https": "https://pbs.twimg.com/profile_images/1.jpg"
+                    }
+                }
+            },
+            "timeline": {
+                "instructions": [{
+                    "addEntries": {
+                        "entries": [
+                            {"content": {"item": {"content": {"user": {"id": "783214"}}}}},
+                            {"content": {"operation": {"cursor": {"value": "next", "cursorType": "Bottom"}}}}
+                        ]
+                    }
+                }]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let (profiles, cursor) = response.parse_users();
+    assert_eq!(cursor.as_deref(), Some("next"));
+    assert_eq!(profiles.len(), 1);
+    assert_eq!(profiles[0].username, "Twitter");
+    assert_eq!(profiles[0].followers_count, 100);
+    assert_eq!(profiles[0].following_count, 10);
+    assert_eq!(profiles[0].likes_count, 7);
+    assert_eq!(profiles[0].website.as_deref(), Some("https://about.twitter.com"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_parse_media_and_place() {
+    let raw: TweetRaw = serde_json::from_str(
+        r#"{
+            "created_at": "Wed Oct 10 20:19:24 +0000 2018",
+            "id_str": "1050118621198921728",
+            "text": "media",
+            "entities": {},
+            "extended_entities": {"media": [
+                {"id_str": "1", "media_url_https": "https://pbs.twimg.com/media/1.jpg", "type": "photo", "ext_alt_text": "a cat"},
+                {"id_str": "2", "media_url_https": "https://pbs.twimg.com/ext_tw_video_thumb/2.jpg", "type": "video",
+                 "video_info": {"duration_millis": 30000, "variants": [
+                    {"bitrate": 256000, "content_type": "video/mp4", "url": "https://video.twimg.com/low.mp4"},
+                    {"content_type": "application/x-mpegURL", "url": "https://video.twimg.com/pl.m3u8"},
+                    {"bitrate": 2176000, "content_type": "video/mp4", "url": "https://video.twimg.com/high.mp4"}
+                 ]}},
+                {"id_str": "3", "media_url_https": "https://pbs.twimg.com/tweet_video_thumb/3.jpg", "type": "animated_gif",
+                 "video_info": {"variants": [{"bitrate": 0, "content_type": "video/mp4", "url": "https://video.twimg.com/3.mp4"}]}}
+            ]},
+            "user_id_str": "1",
+            "is_quote_status": false,
+            "retweet_count": 0,
+            "favorite_count": 0,
+            "retweeted": false,
+            "coordinates": {"type": "Point", "coordinates": [-73.99, 40.73]},
+            "place": {
+                "id": "01a9a39529b27f36", "place_type": "city", "name": "Manhattan",
+                "full_name": "Manhattan, NY", "country_code": "US", "country": "United States",
+                "bounding_box": {"type": "Polygon", "coordinates": [[[-74.02, 40.68], [-73.9, 40.68], [-73.9, 40.87], [-74.02, 40.87]]]}
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let tweet = raw.to_tweet("user").unwrap();
+    let place = tweet.place.as_ref().unwrap();
+    assert_eq!(place.full_name, "Manhattan, NY");
+    assert_eq!(place.bounding_box.as_ref().unwrap().coordinates[0].len(), 4);
+    assert_eq!(tweet.coordinates.unwrap().latitude, 40.73);
+    assert_eq!(tweet.photos.len(), 1);
+    assert_eq!(tweet.photos[0].alt_text.as_deref(), Some("a cat"));
+    assert_eq!(tweet.videos.len(), 1);
+    assert_eq!(tweet.videos[0].url, "https://video.twimg.com/high.mp4");
+    assert_eq!(tweet.videos[0].duration_ms, Some(30000));
+    assert_eq!(tweet.videos[0].variants.len(), 3);
+    assert!(tweet.videos[0].variants[1].is_hls());
+    assert_eq!(tweet.gifs.len(), 1);
+    assert_eq!(tweet.gifs[0].url, "https://video.twimg.com/3.mp4");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_parse_quoted_and_retweeted() {
+    let tweet = |id: &str, user: &str, extra: &str| -> String {
+        format!(
+            r#""{id}": {{
+                "created_at": "Wed Oct 10 20:19:24 +0000 2018", "id_str": "{id}", "text": "tweet {id}",
+                "entities": {{}}, "user_id_str": "{user}", "is_quote_status": false,
+                "retweet_count": 0, "favorite_count": 0, "retweeted": false {extra}
+            }}"#
+        )
+    };
+    let user = |id: &str, name: &str| -> String {
+        format!(
+            r#""{id}": {{"id_str": "{id}", "name": "{name}", "screen_name": "{name}", "created_at": "Tue Feb 20 14:35:54 +0000 2007"}}"#
+        )
+    };
+
+    let body = format!(
+        r#"{{
+            "globalObjects": {{"tweets": {{{}, {}, {}}}, "users": {{{}, {}}}}},
+            "timeline": {{"instructions": []}}
+        }}"#,
+        tweet("1", "10", ""),
+        tweet("2", "20", r#", "quoted_status_id_str": "1""#),
+        tweet("3", "20", r#", "retweeted_status_id_str": "2""#),
+        user("10", "author"),
+        user("20", "reposter"),
+    );
+
+    let response: TwitterTimelineResponse = serde_json::from_str(&body).unwrap();
+
+    let quote = response.parse_tweet("2").unwrap();
+    assert!(quote.is_quoted);
+    assert!(!quote.is_retweet);
+    assert_eq!(quote.quoted_status.as_ref().unwrap().username, "author");
+
+    let retweet = response.parse_tweet("3").unwrap();
+    assert!(retweet.is_retweet);
+    let original = retweet.retweeted_status.unwrap();
+    assert_eq!(original.text, "tweet 2");
+    assert_eq!(original.quoted_status.unwrap().text, "tweet 1");
+
+    let body = format!(
+        r#"{{
+            "globalObjects": {{"tweets": {{{}}}, "users": {{{}}}}},
+            "timeline": {{"instructions": []}}
+        }}"#,
+        tweet("4", "10", r#", "quoted_status_id_str": "4""#),
+        user("10", "author"),
+    );
+    let response: TwitterTimelineResponse = serde_json::from_str(&body).unwrap();
+    let quote = response.parse_tweet("4").unwrap();
+    let nested = quote.quoted_status.unwrap().quoted_status.unwrap();
+    assert!(nested.quoted_status.is_none());
+}
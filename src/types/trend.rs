@@ -0,0 +1,129 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendRaw {
+    pub name: String,
+    pub url: Option<TrendUrl>,
+    pub trend_metadata: Option<TrendMetadata>,
+    pub promoted_metadata: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TrendUrl {
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendMetadata {
+    pub meta_description: Option<String>,
+    pub domain_context: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trend {
+    pub name: String,
+    pub query: String,
+    pub tweet_volume: Option<i64>,
+    pub is_promoted: bool,
+    pub domain_context: Option<String>,
+}
+
+impl From<&TrendRaw> for Trend {
+    fn from(value: &TrendRaw) -> Self {
+        let query = value
+            .url
+            .as_ref()
+            .and_then(|u| search_query(&u.url))
+            .unwrap_or_else(|| value.name.to_owned());
+
+        let metadata = value.trend_metadata.as_ref();
+
+        Self {
+            name: value.name.to_owned(),
+            query,
+            tweet_volume: metadata
+                .and_then(|m| m.meta_description.as_deref())
+                .and_then(parse_tweet_volume),
+            is_promoted: value.promoted_metadata.is_some(),
+            domain_context: metadata.and_then(|m| m.domain_context.to_owned()),
+        }
+    }
+}
+
+fn search_query(url: &str) -> Option<String> {
+    let (_, params) = url.split_once('?')?;
+    params
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| *key == "query")
+        .and_then(|(_, value)| {
+            urlencoding::decode(&value.replace('+', " "))
+                .ok()
+                .map(|value| value.into_owned())
+        })
+}
+
+/// Parses descriptions such as "12.3K Tweets" or "1,204 posts".
+fn parse_tweet_volume(description: &str) -> Option<i64> {
+    let amount = description.split_whitespace().next()?.replace(',', "");
+    let (number, multiplier) = match amount.chars().last()? {
+        'K' | 'k' => (&amount[..amount.len() - 1], 1_000.0),
+        'M' | 'm' => (&amount[..amount.len() - 1], 1_000_000.0),
+        _ => (amount.as_str(), 1.0),
+    };
+
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|n| (n * multiplier).round() as i64)
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_trend_from_raw() {
+    let raw: TrendRaw = serde_json::from_str(
+        r##"{
+            "name": "#RustLang",
+            "url": {"urlType": "DeepLink", "url": "twitter://search/?query=%23RustLang+news&src=trend_click&vertical=trends"},
+            "trendMetadata": {"metaDescription": "12.3K Tweets", "domainContext": "Trending in Technology"}
+        }"##,
+    )
+    .unwrap();
+
+    let trend = Trend::from(&raw);
+    assert_eq!(trend.query, "#RustLang news");
+    assert_eq!(trend.tweet_volume, Some(12_300));
+    assert_eq!(trend.domain_context.as_deref(), Some("Trending in Technology"));
+    assert!(!trend.is_promoted);
+    assert_eq!(parse_tweet_volume("1,204 posts"), Some(1_204));
+    assert_eq!(parse_tweet_volume("Trending"), None);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_trends_for_location() {
+    use crate::mock::{MockTwitter, GUIDE_PATH};
+
+    let mock = MockTwitter::start().await;
+    let trends = mock.scraper().get_trends_for_location(23424977).await.unwrap();
+
+    assert_eq!(trends.len(), 2);
+    assert_eq!(trends[0].name, "#RustLang");
+    assert_eq!(trends[0].tweet_volume, Some(12_300));
+    assert!(!trends[0].is_promoted);
+    assert_eq!(trends[1].query, "\"Super Bowl\"");
+    assert_eq!(trends[1].domain_context.as_deref(), Some("Sports"));
+    assert!(trends[1].is_promoted);
+
+    let requests = mock.server().received_requests().await.unwrap();
+    let guide = requests
+        .iter()
+        .find(|request| request.url.path() == GUIDE_PATH)
+        .unwrap();
+    assert!(guide
+        .url
+        .query_pairs()
+        .any(|(key, value)| key == "woeid" && value == "23424977"));
+}
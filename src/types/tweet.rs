@@ -1,5 +1,11 @@
 use chrono::NaiveDateTime;
 
+use super::{
+    photo::Photo,
+    place::{Coordinates, Place},
+    video::Video,
+};
+
 #[derive(Debug)]
 pub struct Tweet {
     pub hashtags: Vec<String>,
@@ -20,5 +26,12 @@ pub struct Tweet {
     pub user_id: i64,
     pub username: String,
     pub sensitive_content: bool,
-    pub mentions: Vec<String>
+    pub mentions: Vec<String>,
+    pub photos: Vec<Photo>,
+    pub videos: Vec<Video>,
+    pub gifs: Vec<Video>,
+    pub place: Option<Place>,
+    pub coordinates: Option<Coordinates>,
+    pub quoted_status: Option<Box<Tweet>>,
+    pub retweeted_status: Option<Box<Tweet>>,
 }
@@ -1,6 +1,23 @@
-#[derive(Debug)]
-pub struct Video{
+use serde::Deserialize;
+
+#[derive(Debug, Clone)]
+pub struct Video {
     pub id: String,
     pub preview: String,
-    pub url: String
-}
\ No newline at end of file
+    pub url: String,
+    pub duration_ms: Option<i64>,
+    pub variants: Vec<VideoVariant>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VideoVariant {
+    pub bitrate: Option<i64>,
+    pub content_type: String,
+    pub url: String,
+}
+
+impl VideoVariant {
+    pub fn is_hls(&self) -> bool {
+        self.content_type == "application/x-mpegURL"
+    }
+}